@@ -7,6 +7,7 @@ use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, FromVal, Raw
 pub struct PriceData {
     pub price: i128,
     pub timestamp: u64,
+    pub decimals: u32,
 }
 
 pub struct PriceOracleConsumerContract;
@@ -25,6 +26,11 @@ impl PriceOracleConsumerContract {
         e.invoke_contract(&contract_id, &Symbol::short("decimals"), Vec::new(&e))
     }
 
+    pub fn asset_decimals(e: Env, contract_id: BytesN<32>, asset: Address) -> u32 {
+        let args: Vec<RawVal> = Vec::from_array(&e, [asset.to_raw()]);
+        e.invoke_contract(&contract_id, &Symbol::new(&e, "asset_decimals"), args)
+    }
+
     pub fn resolution(e: Env, contract_id: BytesN<32>) -> u32 {
         e.invoke_contract(&contract_id, &Symbol::short("resolution"), Vec::new(&e))
     }
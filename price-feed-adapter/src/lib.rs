@@ -0,0 +1,179 @@
+#![no_std]
+
+mod test;
+
+use soroban_sdk::{
+    contracterror, contractimpl, contracttype, panic_with_error, Address, Bytes, BytesN, Env, RawVal, Symbol, Vec,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+    pub decimals: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The feed the adapter is bound to.
+pub struct FeedConfig {
+    /// The wrapped oracle contract id.
+    pub oracle: BytesN<32>,
+    /// The asset of the feed.
+    pub asset: Address,
+    /// The quote asset of the feed. None means the oracle base asset.
+    pub quote_asset: Option<Address>,
+    /// The human readable description of the feed.
+    pub description: Bytes,
+}
+
+#[contracttype]
+enum DataKey {
+    Admin,
+    Config,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+}
+
+pub struct PriceFeedAdapterContract;
+
+#[contractimpl]
+impl PriceFeedAdapterContract {
+    /// Binds the adapter to the asset or the pair of the given oracle. Can only be called once by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `admin` - The admin address that deploys the adapter.
+    /// * `config` - The oracle contract id, the asset, the optional quote asset and the feed description.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the admin is not authorized, or if the adapter is already initialized.
+    pub fn init(e: Env, admin: Address, config: FeedConfig) {
+        admin.require_auth();
+        if e.storage().has(&DataKey::Config) {
+            panic_with_error!(&e, Error::AlreadyInitialized);
+        }
+        e.storage().set(&DataKey::Admin, &admin);
+        e.storage().set(&DataKey::Config, &config);
+    }
+
+    /// Returns the admin address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The admin address.
+    pub fn admin(e: Env) -> Address {
+        if !e.storage().has(&DataKey::Admin) {
+            panic_with_error!(&e, Error::NotInitialized);
+        }
+        e.storage().get_unchecked(&DataKey::Admin).unwrap()
+    }
+
+    /// Returns the feed configuration.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The feed configuration.
+    pub fn config(e: Env) -> FeedConfig {
+        get_config(&e)
+    }
+
+    /// Returns the latest price of the feed.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The latest price or None if the oracle has no price for the feed.
+    pub fn latest_answer(e: Env) -> Option<i128> {
+        let price = get_last_price(&e);
+        if price.is_none() {
+            return None;
+        }
+        Some(price.unwrap().price)
+    }
+
+    /// Returns the timestamp of the latest price of the feed.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The timestamp in seconds or None if the oracle has no price for the feed.
+    pub fn latest_timestamp(e: Env) -> Option<u64> {
+        let price = get_last_price(&e);
+        if price.is_none() {
+            return None;
+        }
+        //the oracle timestamps are in milliseconds
+        Some(price.unwrap().timestamp / 1000)
+    }
+
+    /// Returns the number of decimals of the feed prices.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset price decimals, or the oracle decimals for the pairs.
+    pub fn decimals(e: Env) -> u32 {
+        let config = get_config(&e);
+        if config.quote_asset.is_some() {
+            return e.invoke_contract(&config.oracle, &Symbol::short("decimals"), Vec::new(&e));
+        }
+        let args: Vec<RawVal> = Vec::from_array(&e, [config.asset.to_raw()]);
+        e.invoke_contract(&config.oracle, &Symbol::new(&e, "asset_decimals"), args)
+    }
+
+    /// Returns the description of the feed.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the adapter is not initialized.
+    /// 
+    /// # Returns
+    /// 
+    /// The feed description.
+    pub fn description(e: Env) -> Bytes {
+        get_config(&e).description
+    }
+}
+
+fn get_config(e: &Env) -> FeedConfig {
+    if !e.storage().has(&DataKey::Config) {
+        panic_with_error!(e, Error::NotInitialized);
+    }
+    e.storage().get_unchecked(&DataKey::Config).unwrap()
+}
+
+fn get_last_price(e: &Env) -> Option<PriceData> {
+    let config = get_config(e);
+    if config.quote_asset.is_none() {
+        let args: Vec<RawVal> = Vec::from_array(e, [config.asset.to_raw()]);
+        return e.invoke_contract(&config.oracle, &Symbol::short("lastprice"), args);
+    }
+    let args: Vec<RawVal> = Vec::from_array(e, [config.asset.to_raw(), config.quote_asset.unwrap().to_raw()]);
+    e.invoke_contract(&config.oracle, &Symbol::new(e, "x_last_price"), args)
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+#![cfg(test)]
+extern crate std;
+extern crate alloc;
+
+use super::*;
+use alloc::rc::Rc;
+use soroban_sdk::{testutils::Address as _, xdr, Address, Bytes, Env};
+
+use se_price_oracle::{PriceOracleContract, PriceOracleContractClient};
+use shared::{constants::Constants, types::config_data::ConfigData};
+
+pub struct OracleContract;
+
+#[contractimpl]
+impl OracleContract {
+    pub fn decimals(_e: Env) -> u32 {
+        14
+    }
+
+    pub fn asset_decimals(_e: Env, _asset: Address) -> u32 {
+        7
+    }
+
+    pub fn lastprice(_e: Env, _asset: Address) -> Option<PriceData> {
+        Some(PriceData {
+            price: 100,
+            timestamp: 600_000,
+            decimals: 7,
+        })
+    }
+
+    pub fn x_last_price(_e: Env, _base_asset: Address, _quote_asset: Address) -> Option<PriceData> {
+        None
+    }
+}
+
+pub fn register_account(e: &Env, account: &[u8; 32]) {
+    let account_id = xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256(
+        account.clone(),
+    )));
+    e.host()
+            .with_mut_storage(|storage| {
+                let k = Rc::new(xdr::LedgerKey::Account(xdr::LedgerKeyAccount {
+                    account_id: account_id.clone(),
+                }));
+
+                let budget = e.host().budget_cloned();
+
+                if !storage.has(
+                    &k,
+                    &budget,
+                )? {
+                    let v = Rc::new(xdr::LedgerEntry {
+                        data: xdr::LedgerEntryData::Account(xdr::AccountEntry {
+                            account_id: account_id.clone(),
+                            balance: 0,
+                            flags: 0,
+                            home_domain: Default::default(),
+                            inflation_dest: None,
+                            num_sub_entries: 0,
+                            seq_num: xdr::SequenceNumber(0),
+                            thresholds: xdr::Thresholds([1; 4]),
+                            signers: xdr::VecM::default(),
+                            ext: xdr::AccountEntryExt::V0,
+                        }),
+                        last_modified_ledger_seq: 0,
+                        ext: xdr::LedgerEntryExt::V0,
+                    });
+                    storage.put(
+                        &k,
+                        &v,
+                        &budget,
+                    )?
+                }
+                Ok(())
+            })
+            .unwrap();
+}
+
+fn init_oracle(env: &Env) -> (PriceOracleContractClient, ConfigData) {
+    register_account(env, &Constants::ADMIN);
+
+    let oracle = env.register_contract(None, PriceOracleContract);
+    let client = PriceOracleContractClient::new(env, &oracle);
+
+    let config_data = ConfigData {
+        admin: Address::random(env),
+        period: 30_000_000,
+        assets: Vec::from_array(env, [Address::random(env), Address::random(env)]),
+        base_fee: 0,
+    };
+
+    let default_admin = Address::from_account_id(env, &BytesN::from_array(env, &Constants::ADMIN));
+    client.config(&default_admin, &config_data);
+
+    (client, config_data)
+}
+
+fn init_adapter(env: &Env, oracle: BytesN<32>, asset: Address, quote_asset: Option<Address>) -> PriceFeedAdapterContractClient {
+    let adapter = env.register_contract(None, PriceFeedAdapterContract);
+    let client = PriceFeedAdapterContractClient::new(env, &adapter);
+
+    client.init(
+        &Address::random(env),
+        &FeedConfig {
+            oracle,
+            asset,
+            quote_asset,
+            description: Bytes::from_slice(env, b"XLM / USD"),
+        },
+    );
+
+    client
+}
+
+#[test]
+fn asset_feed_test() {
+    let env = Env::default();
+    let oracle = env.register_contract(None, OracleContract);
+    let client = init_adapter(&env, oracle, Address::random(&env), None);
+
+    assert_eq!(client.latest_answer(), Some(100));
+    assert_eq!(client.latest_timestamp(), Some(600));
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.description(), Bytes::from_slice(&env, b"XLM / USD"));
+}
+
+#[test]
+fn pair_feed_test() {
+    let env = Env::default();
+    let oracle = env.register_contract(None, OracleContract);
+    let client = init_adapter(&env, oracle, Address::random(&env), Some(Address::random(&env)));
+
+    assert_eq!(client.latest_answer(), None);
+    assert_eq!(client.latest_timestamp(), None);
+    assert_eq!(client.decimals(), 14);
+}
+
+#[test]
+fn oracle_feed_test() {
+    let env = Env::default();
+    let (oracle, config_data) = init_oracle(&env);
+
+    let asset = config_data.assets.get_unchecked(0).unwrap();
+    let quote_asset = config_data.assets.get_unchecked(1).unwrap();
+
+    //the asset price has its own decimals, the quote asset price has the oracle decimals
+    oracle.set_asset_decimals(&config_data.admin, &asset, &7);
+    let updates = Vec::from_array(&env, [2 * 10i128.pow(7), 4 * 10i128.pow(14)]);
+    oracle.set_price(&config_data.admin, &updates, &600_000);
+
+    let asset_feed = init_adapter(&env, oracle.contract_id.clone(), asset.clone(), None);
+    assert_eq!(asset_feed.latest_answer(), Some(2 * 10i128.pow(7)));
+    assert_eq!(asset_feed.latest_timestamp(), Some(600));
+    assert_eq!(asset_feed.decimals(), 7);
+
+    let pair_feed = init_adapter(&env, oracle.contract_id.clone(), asset, Some(quote_asset));
+    assert_eq!(pair_feed.latest_answer(), Some(5 * 10i128.pow(13)));
+    assert_eq!(pair_feed.latest_timestamp(), Some(600));
+    assert_eq!(pair_feed.decimals(), 14);
+}
+
+#[test]
+#[should_panic]
+fn init_twice_test() {
+    let env = Env::default();
+    let oracle = env.register_contract(None, OracleContract);
+    let client = init_adapter(&env, oracle, Address::random(&env), None);
+
+    client.init(&client.admin(), &client.config());
+}
@@ -1,21 +1,27 @@
-use soroban_sdk::{BytesN, Env};
-
-
+use soroban_sdk::{Address, Env};
 
 use shared::types::{
-    data_key::DataKey,
+    billing_mode::BillingMode, data_key::DataKey,
 };
 
+use super::{env_fee_asset_extensions::EnvFeeAssetExtensions, env_treasury_extensions::EnvTreasuryExtensions};
+
 pub trait EnvBalanceExtensions {
     fn get_base_fee(&self) -> Option<i128>;
 
     fn set_base_fee(&self, base_fee: i128);
 
-    fn has_sufficient_balance(&self, account: BytesN<32>, amount: i128) -> bool;
+    fn get_billing_mode(&self) -> BillingMode;
+
+    fn set_billing_mode(&self, mode: BillingMode);
+
+    fn has_sufficient_balance(&self, account: Address, amount: i128) -> bool;
+
+    fn try_inc_balance(&self, account: Address, amount: i128) -> bool;
 
-    fn try_inc_balance(&self, account: BytesN<32>, amount: i128) -> bool;
+    fn try_charge(&self, account: Address, amount: i128) -> bool;
 
-    fn get_balance(&self, account: BytesN<32>) -> Option<i128>;
+    fn get_balance(&self, account: Address) -> Option<i128>;
 }
 
 impl EnvBalanceExtensions for Env {
@@ -30,12 +36,23 @@ impl EnvBalanceExtensions for Env {
         self.storage().set(&DataKey::BaseFee, &base_fee);
     }
 
-    fn has_sufficient_balance(&self, account: BytesN<32>, amount: i128) -> bool {
+    fn get_billing_mode(&self) -> BillingMode {
+        if !self.storage().has(&DataKey::BillingMode) {
+            return BillingMode::Caller;
+        }
+        self.storage().get_unchecked(&DataKey::BillingMode).unwrap()
+    }
+
+    fn set_billing_mode(&self, mode: BillingMode) {
+        self.storage().set(&DataKey::BillingMode, &mode);
+    }
+
+    fn has_sufficient_balance(&self, account: Address, amount: i128) -> bool {
         let account_balance = self.get_balance(account.clone()).unwrap_or_else(|| 0);
         amount < 0 && account_balance < (amount * -1)
     }
 
-    fn try_inc_balance(&self, account: BytesN<32>, amount: i128) -> bool {
+    fn try_inc_balance(&self, account: Address, amount: i128) -> bool {
         let mut account_balance = self.get_balance(account.clone()).unwrap_or_else(|| 0);
         account_balance += amount;
         if account_balance < 0 {
@@ -45,15 +62,50 @@ impl EnvBalanceExtensions for Env {
         true
     }
 
-    fn get_balance(&self, account: BytesN<32>) -> Option<i128> {
-        let balance_key = DataKey::Balance(account);
+    fn try_charge(&self, account: Address, amount: i128) -> bool {
+        //the credit can't be withdrawn, so it is spent first
+        if self.try_charge_credit(account.clone(), amount) {
+            self.record_credit_charge(amount);
+            return true;
+        }
+        if !self.try_inc_balance(account, -amount) {
+            return false;
+        }
+        self.record_charge(amount);
+        true
+    }
+
+    fn get_balance(&self, account: Address) -> Option<i128> {
+        let balance_key = DataKey::AccountBalance(account.clone());
         if self.storage().has(&balance_key) {
             return Some(self.storage().get_unchecked(&balance_key).unwrap());
         }
+        //fall back to the balance stored by the contract id
+        let legacy_key = get_legacy_balance_key(&self, account);
+        if legacy_key.is_some() {
+            return Some(self.storage().get_unchecked(&legacy_key.unwrap()).unwrap());
+        }
         None
     }
 }
 
-fn set_balance(e: &Env, account: BytesN<32>, amount: i128) {
-    e.storage().set(&DataKey::Balance(account), &amount);
-}
\ No newline at end of file
+fn set_balance(e: &Env, account: Address, amount: i128) {
+    //the legacy balance is already included in the amount
+    let legacy_key = get_legacy_balance_key(e, account.clone());
+    if legacy_key.is_some() {
+        e.storage().remove(&legacy_key.unwrap());
+    }
+    e.storage().set(&DataKey::AccountBalance(account), &amount);
+}
+
+fn get_legacy_balance_key(e: &Env, account: Address) -> Option<DataKey> {
+    let contract_id = account.contract_id();
+    if contract_id.is_none() {
+        return None;
+    }
+    let legacy_key = DataKey::Balance(contract_id.unwrap());
+    if !e.storage().has(&legacy_key) {
+        return None;
+    }
+    Some(legacy_key)
+}
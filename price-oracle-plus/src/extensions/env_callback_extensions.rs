@@ -0,0 +1,50 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use shared::types::{data_key::DataKey, price_callback::PriceCallback};
+
+pub trait EnvCallbackExtensions {
+    fn get_callbacks(&self, asset: Address) -> Vec<PriceCallback>;
+
+    fn set_callbacks(&self, asset: Address, callbacks: &Vec<PriceCallback>);
+
+    fn get_round_invocations(&self, round: u64) -> u32;
+
+    fn set_round_invocations(&self, round: u64, invoked: u32);
+}
+
+impl EnvCallbackExtensions for Env {
+    fn get_callbacks(&self, asset: Address) -> Vec<PriceCallback> {
+        let callbacks_key = DataKey::Callbacks(asset);
+        if !self.storage().has(&callbacks_key) {
+            return Vec::new(&self);
+        }
+        self.storage().get_unchecked(&callbacks_key).unwrap()
+    }
+
+    fn set_callbacks(&self, asset: Address, callbacks: &Vec<PriceCallback>) {
+        let callbacks_key = DataKey::Callbacks(asset);
+        if callbacks.len() == 0 {
+            if self.storage().has(&callbacks_key) {
+                self.storage().remove(&callbacks_key);
+            }
+            return;
+        }
+        self.storage().set(&callbacks_key, callbacks);
+    }
+
+    fn get_round_invocations(&self, round: u64) -> u32 {
+        if !self.storage().has(&DataKey::CallbackRound) {
+            return 0;
+        }
+        //only the last round is tracked
+        let (last_round, invoked): (u64, u32) = self.storage().get_unchecked(&DataKey::CallbackRound).unwrap();
+        if last_round != round {
+            return 0;
+        }
+        invoked
+    }
+
+    fn set_round_invocations(&self, round: u64, invoked: u32) {
+        self.storage().set(&DataKey::CallbackRound, &(round, invoked));
+    }
+}
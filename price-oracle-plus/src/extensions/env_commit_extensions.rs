@@ -0,0 +1,118 @@
+use soroban_sdk::{Address, BytesN, Env, Vec};
+
+use shared::types::{
+    commit_reveal_config::CommitRevealConfig, commitment_key::CommitmentKey, data_key::DataKey,
+};
+
+pub trait EnvCommitExtensions {
+    fn get_commit_reveal_config(&self) -> Option<CommitRevealConfig>;
+
+    fn set_commit_reveal_config(&self, config: &CommitRevealConfig);
+
+    fn get_commitment(&self, provider: Address, timestamp: u64) -> Option<BytesN<32>>;
+
+    fn set_commitment(&self, provider: Address, timestamp: u64, hash: &BytesN<32>);
+
+    fn remove_commitment(&self, provider: Address, timestamp: u64);
+
+    fn get_committers(&self, timestamp: u64) -> Vec<Address>;
+
+    fn start_commit_round(&self, timestamp: u64);
+
+    fn get_missed_reveals(&self, provider: Address) -> u32;
+
+    fn record_missed_reveals(&self, timestamp: u64);
+}
+
+impl EnvCommitExtensions for Env {
+    fn get_commit_reveal_config(&self) -> Option<CommitRevealConfig> {
+        if !self.storage().has(&DataKey::CommitRevealConfig) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&DataKey::CommitRevealConfig).unwrap())
+    }
+
+    fn set_commit_reveal_config(&self, config: &CommitRevealConfig) {
+        if config.commit_window == 0 || config.reveal_window == 0 {
+            if self.storage().has(&DataKey::CommitRevealConfig) {
+                self.storage().remove(&DataKey::CommitRevealConfig);
+            }
+            return;
+        }
+        self.storage().set(&DataKey::CommitRevealConfig, config);
+    }
+
+    fn get_commitment(&self, provider: Address, timestamp: u64) -> Option<BytesN<32>> {
+        let commitment_key = DataKey::Commitment(CommitmentKey { provider, timestamp });
+        if !self.storage().has(&commitment_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&commitment_key).unwrap())
+    }
+
+    fn set_commitment(&self, provider: Address, timestamp: u64, hash: &BytesN<32>) {
+        let commitment_key = DataKey::Commitment(CommitmentKey {
+            provider: provider.clone(),
+            timestamp,
+        });
+        if !self.storage().has(&commitment_key) {
+            let mut committers = self.get_committers(timestamp);
+            committers.push_back(provider);
+            self.storage().set(&DataKey::Committers(timestamp), &committers);
+        }
+        self.storage().set(&commitment_key, hash);
+    }
+
+    fn remove_commitment(&self, provider: Address, timestamp: u64) {
+        let commitment_key = DataKey::Commitment(CommitmentKey { provider, timestamp });
+        if self.storage().has(&commitment_key) {
+            self.storage().remove(&commitment_key);
+        }
+    }
+
+    fn get_committers(&self, timestamp: u64) -> Vec<Address> {
+        let committers_key = DataKey::Committers(timestamp);
+        if !self.storage().has(&committers_key) {
+            return Vec::new(&self);
+        }
+        self.storage().get_unchecked(&committers_key).unwrap()
+    }
+
+    fn start_commit_round(&self, timestamp: u64) {
+        if self.storage().has(&DataKey::CommitRound) {
+            let commit_round: u64 = self.storage().get_unchecked(&DataKey::CommitRound).unwrap();
+            if commit_round == timestamp {
+                return;
+            }
+            //the reveal window of the previous round is closed when the next one starts
+            self.record_missed_reveals(commit_round);
+        }
+        self.storage().set(&DataKey::CommitRound, &timestamp);
+    }
+
+    fn get_missed_reveals(&self, provider: Address) -> u32 {
+        let missed_key = DataKey::MissedReveals(provider);
+        if !self.storage().has(&missed_key) {
+            return 0;
+        }
+        self.storage().get_unchecked(&missed_key).unwrap()
+    }
+
+    fn record_missed_reveals(&self, timestamp: u64) {
+        let committers_key = DataKey::Committers(timestamp);
+        if !self.storage().has(&committers_key) {
+            return;
+        }
+        //the commitments left after the round are the missed reveals
+        for provider in self.get_committers(timestamp).iter() {
+            let provider = provider.unwrap();
+            if self.get_commitment(provider.clone(), timestamp).is_none() {
+                continue;
+            }
+            self.remove_commitment(provider.clone(), timestamp);
+            let missed_reveals = self.get_missed_reveals(provider.clone()) + 1;
+            self.storage().set(&DataKey::MissedReveals(provider), &missed_reveals);
+        }
+        self.storage().remove(&committers_key);
+    }
+}
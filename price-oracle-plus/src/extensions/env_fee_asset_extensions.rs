@@ -0,0 +1,183 @@
+use soroban_sdk::{Address, Env, Map};
+
+use shared::extensions::i128_extensions::I128Extensions;
+use shared::types::{credit_pool::CreditPool, data_key::DataKey, fee_asset_config::FeeAssetConfig, rounding::Rounding};
+
+pub trait EnvFeeAssetExtensions {
+    fn get_fee_assets(&self) -> Map<Address, FeeAssetConfig>;
+
+    fn set_fee_assets(&self, fee_assets: &Map<Address, FeeAssetConfig>);
+
+    fn get_fee_asset_config(&self, asset: Address) -> Option<FeeAssetConfig>;
+
+    fn get_reserve(&self, asset: Address) -> i128;
+
+    fn try_inc_reserve(&self, asset: Address, amount: i128) -> bool;
+
+    fn get_credit_balance(&self, account: Address) -> i128;
+
+    fn try_inc_credit_balance(&self, account: Address, amount: i128) -> bool;
+
+    fn get_sponsor_credit(&self, sponsor: Address) -> i128;
+
+    fn try_inc_sponsor_credit(&self, sponsor: Address, amount: i128) -> bool;
+
+    fn get_credit_pool(&self, asset: Address) -> CreditPool;
+
+    fn try_add_credit(&self, account: Address, asset: Address, tokens: i128, credit: i128) -> bool;
+
+    fn try_add_sponsor_credit(&self, sponsor: Address, asset: Address, tokens: i128, credit: i128) -> bool;
+
+    fn try_charge_credit(&self, account: Address, amount: i128) -> bool;
+
+    fn try_charge_sponsor_credit(&self, sponsor: Address, amount: i128) -> bool;
+}
+
+impl EnvFeeAssetExtensions for Env {
+    fn get_fee_assets(&self) -> Map<Address, FeeAssetConfig> {
+        if !self.storage().has(&DataKey::FeeAssets) {
+            return Map::new(&self);
+        }
+        self.storage().get_unchecked(&DataKey::FeeAssets).unwrap()
+    }
+
+    fn set_fee_assets(&self, fee_assets: &Map<Address, FeeAssetConfig>) {
+        self.storage().set(&DataKey::FeeAssets, fee_assets);
+    }
+
+    fn get_fee_asset_config(&self, asset: Address) -> Option<FeeAssetConfig> {
+        let config = self.get_fee_assets().get(asset);
+        if config.is_none() {
+            return None;
+        }
+        Some(config.unwrap().unwrap())
+    }
+
+    fn get_reserve(&self, asset: Address) -> i128 {
+        let reserve_key = DataKey::Reserve(asset);
+        if !self.storage().has(&reserve_key) {
+            return 0;
+        }
+        self.storage().get_unchecked(&reserve_key).unwrap()
+    }
+
+    fn try_inc_reserve(&self, asset: Address, amount: i128) -> bool {
+        let reserve = self.get_reserve(asset.clone()) + amount;
+        if reserve < 0 {
+            return false;
+        }
+        self.storage().set(&DataKey::Reserve(asset), &reserve);
+        true
+    }
+
+    fn get_credit_balance(&self, account: Address) -> i128 {
+        let credit_key = DataKey::CreditBalance(account);
+        if !self.storage().has(&credit_key) {
+            return 0;
+        }
+        self.storage().get_unchecked(&credit_key).unwrap()
+    }
+
+    fn try_inc_credit_balance(&self, account: Address, amount: i128) -> bool {
+        let credit = self.get_credit_balance(account.clone()) + amount;
+        if credit < 0 {
+            return false;
+        }
+        self.storage().set(&DataKey::CreditBalance(account), &credit);
+        true
+    }
+
+    fn get_sponsor_credit(&self, sponsor: Address) -> i128 {
+        let credit_key = DataKey::SponsorCredit(sponsor);
+        if !self.storage().has(&credit_key) {
+            return 0;
+        }
+        self.storage().get_unchecked(&credit_key).unwrap()
+    }
+
+    fn try_inc_sponsor_credit(&self, sponsor: Address, amount: i128) -> bool {
+        let credit = self.get_sponsor_credit(sponsor.clone()) + amount;
+        if credit < 0 {
+            return false;
+        }
+        self.storage().set(&DataKey::SponsorCredit(sponsor), &credit);
+        true
+    }
+
+    fn get_credit_pool(&self, asset: Address) -> CreditPool {
+        let pool_key = DataKey::CreditPool(asset);
+        if !self.storage().has(&pool_key) {
+            return CreditPool { tokens: 0, credit: 0 };
+        }
+        self.storage().get_unchecked(&pool_key).unwrap()
+    }
+
+    fn try_add_credit(&self, account: Address, asset: Address, tokens: i128, credit: i128) -> bool {
+        let asset_key = DataKey::CreditAsset(account.clone());
+        if !can_bind_credit_asset(self, &asset_key, &asset, self.get_credit_balance(account.clone())) {
+            return false;
+        }
+        self.storage().set(&asset_key, &asset);
+        self.try_inc_credit_balance(account, credit);
+        add_to_pool(self, asset, tokens, credit);
+        true
+    }
+
+    fn try_add_sponsor_credit(&self, sponsor: Address, asset: Address, tokens: i128, credit: i128) -> bool {
+        let asset_key = DataKey::SponsorCreditAsset(sponsor.clone());
+        if !can_bind_credit_asset(self, &asset_key, &asset, self.get_sponsor_credit(sponsor.clone())) {
+            return false;
+        }
+        self.storage().set(&asset_key, &asset);
+        self.try_inc_sponsor_credit(sponsor, credit);
+        add_to_pool(self, asset, tokens, credit);
+        true
+    }
+
+    fn try_charge_credit(&self, account: Address, amount: i128) -> bool {
+        let asset_key = DataKey::CreditAsset(account.clone());
+        if amount <= 0 || !self.storage().has(&asset_key) || !self.try_inc_credit_balance(account, -amount) {
+            return false;
+        }
+        release_from_pool(self, self.storage().get_unchecked(&asset_key).unwrap(), amount);
+        true
+    }
+
+    fn try_charge_sponsor_credit(&self, sponsor: Address, amount: i128) -> bool {
+        let asset_key = DataKey::SponsorCreditAsset(sponsor.clone());
+        if amount <= 0 || !self.storage().has(&asset_key) || !self.try_inc_sponsor_credit(sponsor, -amount) {
+            return false;
+        }
+        release_from_pool(self, self.storage().get_unchecked(&asset_key).unwrap(), amount);
+        true
+    }
+}
+
+//the credit of an account is backed by a single asset, the asset can be changed once the credit is spent
+fn can_bind_credit_asset(e: &Env, asset_key: &DataKey, asset: &Address, credit: i128) -> bool {
+    if credit == 0 || !e.storage().has(asset_key) {
+        return true;
+    }
+    let current: Address = e.storage().get_unchecked(asset_key).unwrap();
+    &current == asset
+}
+
+fn add_to_pool(e: &Env, asset: Address, tokens: i128, credit: i128) {
+    let mut pool = e.get_credit_pool(asset.clone());
+    pool.tokens += tokens;
+    pool.credit += credit;
+    e.storage().set(&DataKey::CreditPool(asset), &pool);
+}
+
+//moves the tokens backing the charged credit to the reserve, so only the earned tokens can be claimed
+fn release_from_pool(e: &Env, asset: Address, credit: i128) {
+    let mut pool = e.get_credit_pool(asset.clone());
+    let mut tokens = pool.tokens;
+    if credit < pool.credit {
+        tokens = pool.tokens.mul_div(credit, pool.credit, Rounding::Floor).unwrap_or(0);
+    }
+    pool.tokens -= tokens;
+    pool.credit -= credit;
+    e.storage().set(&DataKey::CreditPool(asset.clone()), &pool);
+    e.try_inc_reserve(asset, tokens);
+}
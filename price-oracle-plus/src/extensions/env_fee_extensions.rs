@@ -0,0 +1,85 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use shared::types::{data_key::DataKey, endpoint::Endpoint, endpoint_fee::EndpointFee};
+
+use super::env_balance_extensions::EnvBalanceExtensions;
+
+pub trait EnvFeeExtensions {
+    fn get_endpoint_fee(&self, endpoint: Endpoint) -> EndpointFee;
+
+    fn set_endpoint_fee(&self, endpoint: Endpoint, fee: &EndpointFee);
+
+    fn get_asset_fee(&self, asset: Address) -> Option<i128>;
+
+    fn set_asset_fee(&self, asset: Address, fee: i128);
+
+    fn calc_fee(&self, endpoint: Endpoint, assets: &Vec<Address>, records: u32) -> i128;
+}
+
+impl EnvFeeExtensions for Env {
+    fn get_endpoint_fee(&self, endpoint: Endpoint) -> EndpointFee {
+        let fee_key = DataKey::EndpointFee(endpoint);
+        if !self.storage().has(&fee_key) {
+            return default_endpoint_fee(endpoint);
+        }
+        self.storage().get_unchecked(&fee_key).unwrap()
+    }
+
+    fn set_endpoint_fee(&self, endpoint: Endpoint, fee: &EndpointFee) {
+        self.storage().set(&DataKey::EndpointFee(endpoint), fee);
+    }
+
+    fn get_asset_fee(&self, asset: Address) -> Option<i128> {
+        let fee_key = DataKey::AssetFee(asset);
+        if !self.storage().has(&fee_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&fee_key).unwrap())
+    }
+
+    fn set_asset_fee(&self, asset: Address, fee: i128) {
+        let fee_key = DataKey::AssetFee(asset);
+        if fee == 0 {
+            if self.storage().has(&fee_key) {
+                self.storage().remove(&fee_key);
+            }
+            return;
+        }
+        self.storage().set(&fee_key, &fee);
+    }
+
+    fn calc_fee(&self, endpoint: Endpoint, assets: &Vec<Address>, records: u32) -> i128 {
+        let base_fee = self.get_base_fee().unwrap_or_else(|| 0);
+        let endpoint_fee = self.get_endpoint_fee(endpoint);
+
+        let multiplier =
+            endpoint_fee.multiplier as i128 + endpoint_fee.record_multiplier as i128 * records as i128;
+        let mut fee = base_fee * multiplier;
+
+        //add the asset specific fees
+        for asset in assets.iter() {
+            fee += self.get_asset_fee(asset.unwrap()).unwrap_or_else(|| 0);
+        }
+        fee
+    }
+}
+
+fn default_endpoint_fee(endpoint: Endpoint) -> EndpointFee {
+    let (multiplier, record_multiplier) = match endpoint {
+        Endpoint::Price => (1, 0),
+        Endpoint::LastPrice => (1, 0),
+        Endpoint::XPrice => (2, 0),
+        Endpoint::XLastPrice => (2, 0),
+        Endpoint::Prices => (0, 1),
+        Endpoint::XPrices => (0, 2),
+        Endpoint::Twap => (0, 1),
+        Endpoint::XTwap => (0, 1),
+        Endpoint::PullPrice => (1, 0),
+        Endpoint::Callback => (1, 0),
+        Endpoint::Convert => (2, 0),
+    };
+    EndpointFee {
+        multiplier,
+        record_multiplier,
+    }
+}
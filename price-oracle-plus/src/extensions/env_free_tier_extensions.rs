@@ -0,0 +1,100 @@
+use soroban_sdk::{Address, Env};
+
+use shared::extensions::u64_extensions::U64Extensions;
+use shared::types::{data_key::DataKey, free_tier::FreeTier, free_usage::FreeUsage};
+
+pub trait EnvFreeTierExtensions {
+    fn get_free_tier(&self) -> Option<FreeTier>;
+
+    fn set_free_tier(&self, free_tier: &FreeTier);
+
+    fn get_discount(&self, account: Address) -> Option<u32>;
+
+    fn set_discount(&self, account: Address, discount: u32);
+
+    fn get_free_calls(&self, account: Address) -> u32;
+
+    fn try_use_free_call(&self, account: Address) -> bool;
+}
+
+impl EnvFreeTierExtensions for Env {
+    fn get_free_tier(&self) -> Option<FreeTier> {
+        if !self.storage().has(&DataKey::FreeTier) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&DataKey::FreeTier).unwrap())
+    }
+
+    fn set_free_tier(&self, free_tier: &FreeTier) {
+        if free_tier.calls == 0 || free_tier.period == 0 {
+            if self.storage().has(&DataKey::FreeTier) {
+                self.storage().remove(&DataKey::FreeTier);
+            }
+            return;
+        }
+        self.storage().set(&DataKey::FreeTier, free_tier);
+    }
+
+    fn get_discount(&self, account: Address) -> Option<u32> {
+        let discount_key = DataKey::Discount(account);
+        if !self.storage().has(&discount_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&discount_key).unwrap())
+    }
+
+    fn set_discount(&self, account: Address, discount: u32) {
+        let discount_key = DataKey::Discount(account);
+        if discount == 0 {
+            if self.storage().has(&discount_key) {
+                self.storage().remove(&discount_key);
+            }
+            return;
+        }
+        self.storage().set(&discount_key, &discount);
+    }
+
+    fn get_free_calls(&self, account: Address) -> u32 {
+        let free_tier = self.get_free_tier();
+        if free_tier.is_none() {
+            return 0;
+        }
+        let free_tier = free_tier.unwrap();
+        let usage = get_free_usage(self, account, free_tier.period);
+        if usage.calls >= free_tier.calls {
+            return 0;
+        }
+        free_tier.calls - usage.calls
+    }
+
+    fn try_use_free_call(&self, account: Address) -> bool {
+        let free_tier = self.get_free_tier();
+        if free_tier.is_none() {
+            return false;
+        }
+        let free_tier = free_tier.unwrap();
+        let mut usage = get_free_usage(self, account.clone(), free_tier.period);
+        if usage.calls >= free_tier.calls {
+            return false;
+        }
+        usage.calls += 1;
+        self.storage().set(&DataKey::FreeUsage(account), &usage);
+        true
+    }
+}
+
+fn get_free_usage(e: &Env, account: Address, period: u64) -> FreeUsage {
+    let period_start = e.ledger().timestamp().get_normalized_timestamp(period);
+    let usage_key = DataKey::FreeUsage(account);
+    if e.storage().has(&usage_key) {
+        let usage: FreeUsage = e.storage().get_unchecked(&usage_key).unwrap();
+        if usage.period_start == period_start {
+            return usage;
+        }
+    }
+    //the usage resets at the start of each period
+    FreeUsage {
+        period_start,
+        calls: 0,
+    }
+}
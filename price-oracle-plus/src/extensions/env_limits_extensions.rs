@@ -0,0 +1,86 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use shared::extensions::u64_extensions::U64Extensions;
+use shared::types::{
+    consumer_limits::ConsumerLimits, data_key::DataKey, error::Error, limits_state::LimitsState,
+};
+
+pub trait EnvLimitsExtensions {
+    fn get_limits(&self, account: Address) -> Option<ConsumerLimits>;
+
+    fn set_limits(&self, account: Address, limits: &ConsumerLimits);
+
+    fn panic_if_limits_exceeded(&self, account: Address, fee: i128);
+}
+
+impl EnvLimitsExtensions for Env {
+    fn get_limits(&self, account: Address) -> Option<ConsumerLimits> {
+        let limits_key = DataKey::Limits(account);
+        if !self.storage().has(&limits_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&limits_key).unwrap())
+    }
+
+    fn set_limits(&self, account: Address, limits: &ConsumerLimits) {
+        let limits_key = DataKey::Limits(account.clone());
+        let state_key = DataKey::LimitsState(account);
+        //the tracked consumption is reset with the new limits
+        if self.storage().has(&state_key) {
+            self.storage().remove(&state_key);
+        }
+        if limits.calls_per_ledger == 0 && limits.calls_per_period == 0 && limits.fees_per_period == 0 {
+            if self.storage().has(&limits_key) {
+                self.storage().remove(&limits_key);
+            }
+            return;
+        }
+        self.storage().set(&limits_key, limits);
+    }
+
+    fn panic_if_limits_exceeded(&self, account: Address, fee: i128) {
+        let limits = self.get_limits(account.clone());
+        if limits.is_none() {
+            return;
+        }
+        let limits = limits.unwrap();
+
+        let ledger = self.ledger().sequence();
+        let period_start = self.ledger().timestamp().get_normalized_timestamp(limits.period);
+
+        let state_key = DataKey::LimitsState(account);
+        let mut state = LimitsState {
+            ledger,
+            ledger_calls: 0,
+            period_start,
+            period_calls: 0,
+            period_fees: 0,
+        };
+        if self.storage().has(&state_key) {
+            let prev_state: LimitsState = self.storage().get_unchecked(&state_key).unwrap();
+            if prev_state.ledger == ledger {
+                state.ledger_calls = prev_state.ledger_calls;
+            }
+            if prev_state.period_start == period_start {
+                state.period_calls = prev_state.period_calls;
+                state.period_fees = prev_state.period_fees;
+            }
+        }
+
+        state.ledger_calls += 1;
+        state.period_calls += 1;
+        state.period_fees += fee;
+
+        if limits.calls_per_ledger > 0 && state.ledger_calls > limits.calls_per_ledger {
+            panic_with_error!(self, Error::RateLimitExceeded);
+        }
+        if limits.calls_per_period > 0 && state.period_calls > limits.calls_per_period {
+            panic_with_error!(self, Error::RateLimitExceeded);
+        }
+        if limits.fees_per_period > 0 && state.period_fees > limits.fees_per_period {
+            panic_with_error!(self, Error::SpendingCapExceeded);
+        }
+
+        self.storage().set(&state_key, &state);
+    }
+}
@@ -0,0 +1,123 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use shared::constants::Constants;
+use shared::types::{
+    data_key::DataKey, error::Error, provider_rewards::ProviderRewards, reward_pool::RewardPool,
+};
+
+pub trait EnvRewardsExtensions {
+    fn get_reward_pool(&self) -> RewardPool;
+
+    fn set_reward_pool(&self, reward_pool: &RewardPool);
+
+    fn get_provider_rewards(&self, provider: Address) -> ProviderRewards;
+
+    fn set_provider_rewards(&self, provider: Address, rewards: &ProviderRewards);
+
+    fn get_rewards(&self, provider: Address) -> i128;
+
+    fn distribute_rewards(&self, amount: i128) -> i128;
+
+    fn record_round(&self, provider: Address);
+
+    fn remove_provider_rounds(&self, provider: Address);
+
+    fn record_reward_claim(&self, provider: Address, amount: i128);
+}
+
+impl EnvRewardsExtensions for Env {
+    fn get_reward_pool(&self) -> RewardPool {
+        if !self.storage().has(&DataKey::RewardPool) {
+            return RewardPool {
+                share: 0,
+                total_rounds: 0,
+                reward_per_round: 0,
+            };
+        }
+        self.storage().get_unchecked(&DataKey::RewardPool).unwrap()
+    }
+
+    fn set_reward_pool(&self, reward_pool: &RewardPool) {
+        self.storage().set(&DataKey::RewardPool, reward_pool);
+    }
+
+    fn get_provider_rewards(&self, provider: Address) -> ProviderRewards {
+        let rewards_key = DataKey::ProviderRewards(provider);
+        if !self.storage().has(&rewards_key) {
+            return ProviderRewards {
+                rounds: 0,
+                reward_debt: 0,
+                unclaimed: 0,
+            };
+        }
+        self.storage().get_unchecked(&rewards_key).unwrap()
+    }
+
+    fn set_provider_rewards(&self, provider: Address, rewards: &ProviderRewards) {
+        self.storage().set(&DataKey::ProviderRewards(provider), rewards);
+    }
+
+    fn get_rewards(&self, provider: Address) -> i128 {
+        let rewards = settle(&self.get_reward_pool(), self.get_provider_rewards(provider));
+        rewards.unclaimed
+    }
+
+    fn distribute_rewards(&self, amount: i128) -> i128 {
+        let mut reward_pool = self.get_reward_pool();
+        //the fees stay with the operator until the providers contribute rounds
+        if reward_pool.share == 0 || reward_pool.total_rounds == 0 {
+            return 0;
+        }
+        let rewards = amount * reward_pool.share as i128 / 10000;
+        if rewards == 0 {
+            return 0;
+        }
+        reward_pool.reward_per_round +=
+            rewards * get_precision() / reward_pool.total_rounds as i128;
+        self.set_reward_pool(&reward_pool);
+        rewards
+    }
+
+    fn record_round(&self, provider: Address) {
+        let mut reward_pool = self.get_reward_pool();
+        let mut rewards = settle(&reward_pool, self.get_provider_rewards(provider.clone()));
+        rewards.rounds += 1;
+        rewards.reward_debt = rewards.rounds as i128 * reward_pool.reward_per_round;
+        reward_pool.total_rounds += 1;
+        self.set_provider_rewards(provider, &rewards);
+        self.set_reward_pool(&reward_pool);
+    }
+
+    fn remove_provider_rounds(&self, provider: Address) {
+        let mut reward_pool = self.get_reward_pool();
+        let mut rewards = settle(&reward_pool, self.get_provider_rewards(provider.clone()));
+        //the removed provider keeps the settled rewards, but stops earning
+        reward_pool.total_rounds -= rewards.rounds;
+        rewards.rounds = 0;
+        rewards.reward_debt = 0;
+        self.set_provider_rewards(provider, &rewards);
+        self.set_reward_pool(&reward_pool);
+    }
+
+    fn record_reward_claim(&self, provider: Address, amount: i128) {
+        let mut rewards = settle(&self.get_reward_pool(), self.get_provider_rewards(provider.clone()));
+        if rewards.unclaimed < amount {
+            panic_with_error!(self, Error::InsufficientRewards);
+        }
+        rewards.unclaimed -= amount;
+        self.set_provider_rewards(provider, &rewards);
+    }
+}
+
+fn settle(reward_pool: &RewardPool, rewards: ProviderRewards) -> ProviderRewards {
+    let accrued = rewards.rounds as i128 * reward_pool.reward_per_round;
+    ProviderRewards {
+        rounds: rewards.rounds,
+        reward_debt: accrued,
+        unclaimed: rewards.unclaimed + (accrued - rewards.reward_debt) / get_precision(),
+    }
+}
+
+fn get_precision() -> i128 {
+    10i128.pow(Constants::DECIMALS)
+}
@@ -0,0 +1,144 @@
+use soroban_sdk::{panic_with_error, Address, Env, Map, Vec};
+
+use shared::extensions::env_extensions::EnvExtensions;
+use shared::types::{data_key::DataKey, error::Error};
+
+use super::{env_commit_extensions::EnvCommitExtensions, env_staking_extensions::EnvStakingExtensions};
+
+pub trait EnvRoundExtensions {
+    fn submit_round(&self, provider: Address, updates: Vec<i128>, timestamp: u64) -> Option<Vec<i128>>;
+
+    fn has_submission(&self, provider: Address, timestamp: u64) -> bool;
+
+    fn get_quorum(&self) -> u32;
+
+    fn set_quorum(&self, quorum: u32);
+}
+
+impl EnvRoundExtensions for Env {
+    fn submit_round(&self, provider: Address, updates: Vec<i128>, timestamp: u64) -> Option<Vec<i128>> {
+        if updates.len() != self.get_assets().len() {
+            panic_with_error!(self, Error::InvalidUpdatesLength);
+        }
+
+        //the pending round is finalized when the next round starts
+        if self.storage().has(&DataKey::PendingRound) {
+            let pending_round: u64 = self.storage().get_unchecked(&DataKey::PendingRound).unwrap();
+            if timestamp < pending_round {
+                panic_with_error!(self, Error::RoundFinalized);
+            }
+            if timestamp > pending_round {
+                finalize_round(self, pending_round);
+            }
+        }
+
+        let submissions_key = DataKey::Submissions(timestamp);
+        let mut submissions: Map<Address, Vec<i128>> = Map::new(self);
+        if self.storage().has(&submissions_key) {
+            submissions = self.storage().get_unchecked(&submissions_key).unwrap();
+        }
+        submissions.set(provider, updates);
+
+        self.storage().set(&submissions_key, &submissions);
+        self.storage().set(&DataKey::PendingRound, &timestamp);
+
+        //the round prices are not set until enough providers submit them
+        if submissions.len() < self.get_quorum() {
+            return None;
+        }
+        Some(get_medians(self, &submissions))
+    }
+
+    fn has_submission(&self, provider: Address, timestamp: u64) -> bool {
+        let submissions_key = DataKey::Submissions(timestamp);
+        if !self.storage().has(&submissions_key) {
+            return false;
+        }
+        let submissions: Map<Address, Vec<i128>> = self.storage().get_unchecked(&submissions_key).unwrap();
+        submissions.contains_key(provider)
+    }
+
+    fn get_quorum(&self) -> u32 {
+        if !self.storage().has(&DataKey::Quorum) {
+            //the majority of the registered providers by default
+            return self.get_providers().len() / 2 + 1;
+        }
+        self.storage().get_unchecked(&DataKey::Quorum).unwrap()
+    }
+
+    fn set_quorum(&self, quorum: u32) {
+        self.storage().set(&DataKey::Quorum, &quorum);
+    }
+}
+
+fn finalize_round(e: &Env, timestamp: u64) {
+    e.record_missed_reveals(timestamp);
+
+    let submissions_key = DataKey::Submissions(timestamp);
+    if !e.storage().has(&submissions_key) {
+        return;
+    }
+    let submissions: Map<Address, Vec<i128>> = e.storage().get_unchecked(&submissions_key).unwrap();
+    e.storage().remove(&submissions_key);
+
+    let config = e.get_staking_config();
+    if config.is_none() || config.clone().unwrap().max_deviation == 0 {
+        return;
+    }
+    let config = config.unwrap();
+
+    let medians = get_medians(e, &submissions);
+    for submission in submissions.iter() {
+        let (provider, updates) = submission.unwrap();
+        if !is_deviated(&medians, &updates, config.max_deviation) {
+            continue;
+        }
+        let stake = e.get_provider_stake(provider.clone());
+        let amount = (stake.amount + stake.unbonding) * config.slash_share as i128 / 10000;
+        e.slash_stake(provider, amount);
+    }
+}
+
+fn is_deviated(medians: &Vec<i128>, updates: &Vec<i128>, max_deviation: u32) -> bool {
+    for i in 0..medians.len().min(updates.len()) {
+        let median = medians.get_unchecked(i).unwrap();
+        let price = updates.get_unchecked(i).unwrap();
+        if median <= 0 {
+            continue;
+        }
+        if (price - median).abs() * 10000 > median * max_deviation as i128 {
+            return true;
+        }
+    }
+    false
+}
+
+fn get_medians(e: &Env, submissions: &Map<Address, Vec<i128>>) -> Vec<i128> {
+    let mut medians = Vec::new(e);
+    let assets_len = e.get_assets().len();
+    for i in 0..assets_len {
+        //insertion sort, the number of providers is small
+        let mut prices: Vec<i128> = Vec::new(e);
+        for submission in submissions.iter() {
+            let (_, updates) = submission.unwrap();
+            //the submissions made before the assets were added are shorter
+            if updates.len() <= i {
+                continue;
+            }
+            let price = updates.get_unchecked(i).unwrap();
+            let mut index = 0;
+            while index < prices.len() && prices.get_unchecked(index).unwrap() < price {
+                index += 1;
+            }
+            prices.insert(index, price);
+        }
+        let len = prices.len();
+        let median = if len % 2 == 1 {
+            prices.get_unchecked(len / 2).unwrap()
+        } else {
+            (prices.get_unchecked(len / 2 - 1).unwrap() + prices.get_unchecked(len / 2).unwrap()) / 2
+        };
+        medians.push_back(median);
+    }
+    medians
+}
@@ -0,0 +1,54 @@
+use soroban_sdk::{Address, BytesN, Env, Vec};
+
+use shared::types::{data_key::DataKey, price_data::PriceData};
+
+pub trait EnvSignerExtensions {
+    fn get_signers(&self) -> Vec<BytesN<32>>;
+
+    fn set_signers(&self, signers: &Vec<BytesN<32>>);
+
+    fn get_signer_nonce(&self, signer: BytesN<32>) -> u64;
+
+    fn set_signer_nonce(&self, signer: BytesN<32>, nonce: u64);
+
+    fn get_pull_price(&self, asset: Address) -> Option<PriceData>;
+
+    fn set_pull_price(&self, asset: Address, price: &PriceData);
+}
+
+impl EnvSignerExtensions for Env {
+    fn get_signers(&self) -> Vec<BytesN<32>> {
+        if !self.storage().has(&DataKey::Signers) {
+            return Vec::new(&self);
+        }
+        self.storage().get_unchecked(&DataKey::Signers).unwrap()
+    }
+
+    fn set_signers(&self, signers: &Vec<BytesN<32>>) {
+        self.storage().set(&DataKey::Signers, signers);
+    }
+
+    fn get_signer_nonce(&self, signer: BytesN<32>) -> u64 {
+        let nonce_key = DataKey::SignerNonce(signer);
+        if !self.storage().has(&nonce_key) {
+            return 0;
+        }
+        self.storage().get_unchecked(&nonce_key).unwrap()
+    }
+
+    fn set_signer_nonce(&self, signer: BytesN<32>, nonce: u64) {
+        self.storage().set(&DataKey::SignerNonce(signer), &nonce);
+    }
+
+    fn get_pull_price(&self, asset: Address) -> Option<PriceData> {
+        let price_key = DataKey::PullPrice(asset);
+        if !self.storage().has(&price_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&price_key).unwrap())
+    }
+
+    fn set_pull_price(&self, asset: Address, price: &PriceData) {
+        self.storage().set(&DataKey::PullPrice(asset), price);
+    }
+}
@@ -0,0 +1,76 @@
+use soroban_sdk::{Address, Env};
+
+use shared::types::{data_key::DataKey, sponsorship::Sponsorship};
+
+use super::{env_fee_asset_extensions::EnvFeeAssetExtensions, env_treasury_extensions::EnvTreasuryExtensions};
+
+pub trait EnvSponsorExtensions {
+    fn get_sponsorship(&self, consumer: Address) -> Option<Sponsorship>;
+
+    fn set_sponsorship(&self, consumer: Address, sponsorship: &Sponsorship);
+
+    fn get_sponsor_balance(&self, sponsor: Address) -> Option<i128>;
+
+    fn try_inc_sponsor_balance(&self, sponsor: Address, amount: i128) -> bool;
+
+    fn try_charge_sponsor(&self, consumer: Address, amount: i128) -> bool;
+}
+
+impl EnvSponsorExtensions for Env {
+    fn get_sponsorship(&self, consumer: Address) -> Option<Sponsorship> {
+        let sponsorship_key = DataKey::Sponsorship(consumer);
+        if !self.storage().has(&sponsorship_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&sponsorship_key).unwrap())
+    }
+
+    fn set_sponsorship(&self, consumer: Address, sponsorship: &Sponsorship) {
+        self.storage().set(&DataKey::Sponsorship(consumer), sponsorship);
+    }
+
+    fn get_sponsor_balance(&self, sponsor: Address) -> Option<i128> {
+        let balance_key = DataKey::SponsorBalance(sponsor);
+        if !self.storage().has(&balance_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&balance_key).unwrap())
+    }
+
+    fn try_inc_sponsor_balance(&self, sponsor: Address, amount: i128) -> bool {
+        let mut sponsor_balance = self.get_sponsor_balance(sponsor.clone()).unwrap_or_else(|| 0);
+        sponsor_balance += amount;
+        if sponsor_balance < 0 {
+            return false;
+        }
+        self.storage()
+            .set(&DataKey::SponsorBalance(sponsor), &sponsor_balance);
+        true
+    }
+
+    fn try_charge_sponsor(&self, consumer: Address, amount: i128) -> bool {
+        let sponsorship = self.get_sponsorship(consumer.clone());
+        if sponsorship.is_none() {
+            return false;
+        }
+        let mut sponsorship = sponsorship.unwrap();
+
+        //check the consumer spending cap
+        if sponsorship.cap > 0 && sponsorship.spent + amount > sponsorship.cap {
+            return false;
+        }
+
+        //the credit can't be withdrawn, so it is spent first
+        if self.try_charge_sponsor_credit(sponsorship.sponsor.clone(), amount) {
+            self.record_credit_charge(amount);
+        } else if self.try_inc_sponsor_balance(sponsorship.sponsor.clone(), -amount) {
+            self.record_charge(amount);
+        } else {
+            return false;
+        }
+
+        sponsorship.spent += amount;
+        self.set_sponsorship(consumer, &sponsorship);
+        true
+    }
+}
@@ -0,0 +1,85 @@
+use soroban_sdk::{panic_with_error, Address, Env, Symbol};
+
+use shared::types::{
+    data_key::DataKey, error::Error, provider_stake::ProviderStake, staking_config::StakingConfig,
+};
+
+use super::env_treasury_extensions::EnvTreasuryExtensions;
+
+pub trait EnvStakingExtensions {
+    fn get_staking_config(&self) -> Option<StakingConfig>;
+
+    fn set_staking_config(&self, config: &StakingConfig);
+
+    fn get_provider_stake(&self, provider: Address) -> ProviderStake;
+
+    fn set_provider_stake(&self, provider: Address, stake: &ProviderStake);
+
+    fn slash_stake(&self, provider: Address, amount: i128) -> i128;
+
+    fn panic_if_insufficient_stake(&self, provider: Address);
+}
+
+impl EnvStakingExtensions for Env {
+    fn get_staking_config(&self) -> Option<StakingConfig> {
+        if !self.storage().has(&DataKey::StakingConfig) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&DataKey::StakingConfig).unwrap())
+    }
+
+    fn set_staking_config(&self, config: &StakingConfig) {
+        self.storage().set(&DataKey::StakingConfig, config);
+    }
+
+    fn get_provider_stake(&self, provider: Address) -> ProviderStake {
+        let stake_key = DataKey::ProviderStake(provider);
+        if !self.storage().has(&stake_key) {
+            return ProviderStake {
+                amount: 0,
+                unbonding: 0,
+                unbonding_until: 0,
+            };
+        }
+        self.storage().get_unchecked(&stake_key).unwrap()
+    }
+
+    fn set_provider_stake(&self, provider: Address, stake: &ProviderStake) {
+        let stake_key = DataKey::ProviderStake(provider);
+        if stake.amount == 0 && stake.unbonding == 0 {
+            if self.storage().has(&stake_key) {
+                self.storage().remove(&stake_key);
+            }
+            return;
+        }
+        self.storage().set(&stake_key, stake);
+    }
+
+    fn slash_stake(&self, provider: Address, amount: i128) -> i128 {
+        let mut stake = self.get_provider_stake(provider.clone());
+        //the bonded stake is slashed first, then the unbonding one
+        let mut slashed = amount.min(stake.amount);
+        stake.amount -= slashed;
+        let unbonding_slashed = (amount - slashed).min(stake.unbonding);
+        stake.unbonding -= unbonding_slashed;
+        slashed += unbonding_slashed;
+
+        if slashed == 0 {
+            return 0;
+        }
+        self.set_provider_stake(provider.clone(), &stake);
+        self.record_slash(slashed);
+        self.events().publish((Symbol::short("slashed"), provider), slashed);
+        slashed
+    }
+
+    fn panic_if_insufficient_stake(&self, provider: Address) {
+        let config = self.get_staking_config();
+        if config.is_none() {
+            return;
+        }
+        if self.get_provider_stake(provider).amount < config.unwrap().min_stake {
+            panic_with_error!(self, Error::InsufficientStake);
+        }
+    }
+}
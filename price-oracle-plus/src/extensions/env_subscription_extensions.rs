@@ -0,0 +1,91 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use shared::types::{
+    data_key::DataKey, subscription::Subscription, subscription_plan::SubscriptionPlan,
+};
+
+pub trait EnvSubscriptionExtensions {
+    fn get_plan(&self, plan_id: u32) -> Option<SubscriptionPlan>;
+
+    fn set_plan(&self, plan_id: u32, plan: &SubscriptionPlan);
+
+    fn get_subscription(&self, account: Address) -> Option<Subscription>;
+
+    fn set_subscription(&self, account: Address, subscription: &Subscription);
+
+    fn try_use_subscription(&self, account: Address, assets: &Vec<Address>) -> bool;
+}
+
+impl EnvSubscriptionExtensions for Env {
+    fn get_plan(&self, plan_id: u32) -> Option<SubscriptionPlan> {
+        let plan_key = DataKey::SubscriptionPlan(plan_id);
+        if !self.storage().has(&plan_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&plan_key).unwrap())
+    }
+
+    fn set_plan(&self, plan_id: u32, plan: &SubscriptionPlan) {
+        self.storage().set(&DataKey::SubscriptionPlan(plan_id), plan);
+    }
+
+    fn get_subscription(&self, account: Address) -> Option<Subscription> {
+        let subscription_key = DataKey::Subscription(account);
+        if !self.storage().has(&subscription_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&subscription_key).unwrap())
+    }
+
+    fn set_subscription(&self, account: Address, subscription: &Subscription) {
+        self.storage().set(&DataKey::Subscription(account), subscription);
+    }
+
+    fn try_use_subscription(&self, account: Address, assets: &Vec<Address>) -> bool {
+        let subscription = self.get_subscription(account.clone());
+        if subscription.is_none() {
+            return false;
+        }
+        let mut subscription = subscription.unwrap();
+
+        //remove the expired subscription
+        if subscription.expiration <= self.ledger().timestamp() {
+            self.storage().remove(&DataKey::Subscription(account));
+            return false;
+        }
+
+        //check if the calls limit is reached
+        if subscription.max_calls > 0 && subscription.calls >= subscription.max_calls {
+            return false;
+        }
+
+        if !covers_assets(&subscription.assets, assets) {
+            return false;
+        }
+
+        subscription.calls += 1;
+        self.set_subscription(account, &subscription);
+        true
+    }
+}
+
+fn covers_assets(covered: &Vec<Address>, assets: &Vec<Address>) -> bool {
+    //empty vector means all assets are covered
+    if covered.len() == 0 {
+        return true;
+    }
+    for asset in assets.iter() {
+        let asset = asset.unwrap();
+        let mut is_covered = false;
+        for c in covered.iter() {
+            if c.unwrap() == asset {
+                is_covered = true;
+                break;
+            }
+        }
+        if !is_covered {
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,73 @@
+use soroban_sdk::{Address, Env, Symbol};
+
+use shared::types::{data_key::DataKey, top_up::TopUp};
+
+use super::env_balance_extensions::EnvBalanceExtensions;
+
+pub trait EnvTopUpExtensions {
+    fn get_low_balance(&self, account: Address) -> Option<i128>;
+
+    fn set_low_balance(&self, account: Address, threshold: i128);
+
+    fn get_top_up(&self, account: Address) -> Option<TopUp>;
+
+    fn set_top_up(&self, account: Address, top_up: Option<TopUp>);
+
+    fn publish_if_low_balance(&self, account: Address, prev_balance: i128);
+}
+
+impl EnvTopUpExtensions for Env {
+    fn get_low_balance(&self, account: Address) -> Option<i128> {
+        let threshold_key = DataKey::LowBalance(account);
+        if !self.storage().has(&threshold_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&threshold_key).unwrap())
+    }
+
+    fn set_low_balance(&self, account: Address, threshold: i128) {
+        let threshold_key = DataKey::LowBalance(account);
+        if threshold <= 0 {
+            if self.storage().has(&threshold_key) {
+                self.storage().remove(&threshold_key);
+            }
+            return;
+        }
+        self.storage().set(&threshold_key, &threshold);
+    }
+
+    fn get_top_up(&self, account: Address) -> Option<TopUp> {
+        let top_up_key = DataKey::TopUp(account);
+        if !self.storage().has(&top_up_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&top_up_key).unwrap())
+    }
+
+    fn set_top_up(&self, account: Address, top_up: Option<TopUp>) {
+        let top_up_key = DataKey::TopUp(account);
+        if top_up.is_none() {
+            if self.storage().has(&top_up_key) {
+                self.storage().remove(&top_up_key);
+            }
+            return;
+        }
+        self.storage().set(&top_up_key, &top_up.unwrap());
+    }
+
+    fn publish_if_low_balance(&self, account: Address, prev_balance: i128) {
+        let threshold = self.get_low_balance(account.clone());
+        if threshold.is_none() {
+            return;
+        }
+        let threshold = threshold.unwrap();
+        let balance = self.get_balance(account.clone()).unwrap_or(0);
+        //publish only when the threshold is crossed, not on every read below it
+        if prev_balance >= threshold && balance < threshold {
+            self.events().publish(
+                (Symbol::short("low_bal"), account),
+                (balance, threshold),
+            );
+        }
+    }
+}
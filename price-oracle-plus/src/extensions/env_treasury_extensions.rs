@@ -0,0 +1,155 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use shared::extensions::env_extensions::EnvExtensions;
+use shared::types::{data_key::DataKey, error::Error, treasury::Treasury};
+
+use super::env_rewards_extensions::EnvRewardsExtensions;
+
+pub trait EnvTreasuryExtensions {
+    fn get_treasury(&self) -> Treasury;
+
+    fn set_treasury(&self, treasury: &Treasury);
+
+    fn record_deposit(&self, amount: i128);
+
+    fn record_withdrawal(&self, amount: i128);
+
+    fn record_charge(&self, amount: i128);
+
+    fn record_credit(&self, amount: i128);
+
+    fn record_credit_charge(&self, amount: i128);
+
+    fn record_claim(&self, amount: i128);
+
+    fn record_rewards_claim(&self, amount: i128);
+
+    fn record_stake(&self, amount: i128);
+
+    fn record_slash(&self, amount: i128);
+
+    fn get_fee_manager(&self) -> Address;
+
+    fn set_fee_manager(&self, fee_manager: &Address);
+
+    fn panic_if_not_fee_manager(&self, invoker: &Address);
+}
+
+impl EnvTreasuryExtensions for Env {
+    fn get_treasury(&self) -> Treasury {
+        if !self.storage().has(&DataKey::Treasury) {
+            return Treasury {
+                deposits: 0,
+                earned: 0,
+                claimed: 0,
+                rewards: 0,
+                stakes: 0,
+                credits: 0,
+            };
+        }
+        self.storage().get_unchecked(&DataKey::Treasury).unwrap()
+    }
+
+    fn set_treasury(&self, treasury: &Treasury) {
+        self.storage().set(&DataKey::Treasury, treasury);
+    }
+
+    fn record_deposit(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        treasury.deposits += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_withdrawal(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        treasury.deposits -= amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_charge(&self, amount: i128) {
+        if amount == 0 {
+            return;
+        }
+        let rewards = self.distribute_rewards(amount);
+        let mut treasury = self.get_treasury();
+        treasury.deposits -= amount;
+        treasury.earned += amount - rewards;
+        treasury.rewards += rewards;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_credit(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        treasury.credits += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_credit_charge(&self, amount: i128) {
+        if amount == 0 {
+            return;
+        }
+        //the credit charges are earned as the reserves, the providers share is paid from the fee asset earnings
+        let mut treasury = self.get_treasury();
+        let mut rewards = 0;
+        if treasury.earned >= amount {
+            rewards = self.distribute_rewards(amount);
+        }
+        treasury.credits -= amount;
+        treasury.earned -= rewards;
+        treasury.rewards += rewards;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_claim(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        if treasury.earned < amount {
+            panic_with_error!(self, Error::InsufficientFees);
+        }
+        treasury.earned -= amount;
+        treasury.claimed += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_rewards_claim(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        treasury.rewards -= amount;
+        treasury.claimed += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_stake(&self, amount: i128) {
+        let mut treasury = self.get_treasury();
+        treasury.stakes += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn record_slash(&self, amount: i128) {
+        if amount == 0 {
+            return;
+        }
+        //the slashed stakes are earned by the operator
+        let mut treasury = self.get_treasury();
+        treasury.stakes -= amount;
+        treasury.earned += amount;
+        self.set_treasury(&treasury);
+    }
+
+    fn get_fee_manager(&self) -> Address {
+        if !self.storage().has(&DataKey::FeeManager) {
+            //the admin manages the fees if the fee manager is not set
+            return self.get_admin();
+        }
+        self.storage().get_unchecked(&DataKey::FeeManager).unwrap()
+    }
+
+    fn set_fee_manager(&self, fee_manager: &Address) {
+        self.storage().set(&DataKey::FeeManager, fee_manager);
+    }
+
+    fn panic_if_not_fee_manager(&self, invoker: &Address) {
+        invoker.require_auth();
+        if invoker != &self.get_fee_manager() {
+            panic_with_error!(self, Error::Unauthorized);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use shared::extensions::u64_extensions::U64Extensions;
+use shared::types::{
+    data_key::DataKey, endpoint::Endpoint, usage_data::UsageData, usage_key::UsageKey,
+};
+
+//one day
+const DEFAULT_USAGE_PERIOD: u64 = 86400;
+
+pub trait EnvUsageExtensions {
+    fn get_usage_period(&self) -> u64;
+
+    fn set_usage_period(&self, period: u64);
+
+    fn get_usage(&self, account: Address, period: u64) -> Option<UsageData>;
+
+    fn get_usage_range(&self, account: Address, from: u64, to: u64, max_periods: u32) -> Option<Vec<UsageData>>;
+
+    fn record_usage(&self, account: Address, endpoint: Endpoint, fee: i128);
+}
+
+impl EnvUsageExtensions for Env {
+    fn get_usage_period(&self) -> u64 {
+        if !self.storage().has(&DataKey::UsagePeriod) {
+            return DEFAULT_USAGE_PERIOD;
+        }
+        self.storage().get_unchecked(&DataKey::UsagePeriod).unwrap()
+    }
+
+    fn set_usage_period(&self, period: u64) {
+        self.storage().set(&DataKey::UsagePeriod, &period);
+    }
+
+    fn get_usage(&self, account: Address, period: u64) -> Option<UsageData> {
+        let usage_key = DataKey::Usage(UsageKey { account, period });
+        if !self.storage().has(&usage_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&usage_key).unwrap())
+    }
+
+    fn get_usage_range(&self, account: Address, from: u64, to: u64, max_periods: u32) -> Option<Vec<UsageData>> {
+        let usage_period = self.get_usage_period();
+        let mut period = from.get_normalized_timestamp(usage_period);
+        if to < period || (to - period) / usage_period >= max_periods as u64 {
+            return None;
+        }
+
+        let mut usage = Vec::new(&self);
+        while period <= to {
+            let period_usage = self.get_usage(account.clone(), period);
+            if period_usage.is_some() {
+                usage.push_back(period_usage.unwrap());
+            }
+            period += usage_period;
+        }
+        Some(usage)
+    }
+
+    fn record_usage(&self, account: Address, endpoint: Endpoint, fee: i128) {
+        let period = self.ledger().timestamp().get_normalized_timestamp(self.get_usage_period());
+        let mut usage = self.get_usage(account.clone(), period).unwrap_or_else(|| UsageData {
+            period,
+            calls: Map::new(&self),
+            fees: 0,
+        });
+
+        let calls = usage.calls.get(endpoint).unwrap_or(Ok(0)).unwrap();
+        usage.calls.set(endpoint, calls + 1);
+        usage.fees += fee;
+
+        self.storage()
+            .set(&DataKey::Usage(UsageKey { account, period }), &usage);
+    }
+}
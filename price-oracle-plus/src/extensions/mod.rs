@@ -1 +1,16 @@
-pub mod env_balance_extensions;
\ No newline at end of file
+pub mod env_balance_extensions;
+pub mod env_subscription_extensions;
+pub mod env_treasury_extensions;
+pub mod env_fee_extensions;
+pub mod env_sponsor_extensions;
+pub mod env_fee_asset_extensions;
+pub mod env_free_tier_extensions;
+pub mod env_usage_extensions;
+pub mod env_limits_extensions;
+pub mod env_top_up_extensions;
+pub mod env_rewards_extensions;
+pub mod env_staking_extensions;
+pub mod env_round_extensions;
+pub mod env_signer_extensions;
+pub mod env_commit_extensions;
+pub mod env_callback_extensions;
\ No newline at end of file
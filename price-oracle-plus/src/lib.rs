@@ -4,11 +4,33 @@ mod test;
 mod extensions;
 
 use shared::constants::Constants;
+use shared::limits::Limits;
 use shared::price_oracle::PriceOracle;
-use shared::extensions::{env_extensions::EnvExtensions};
-use shared::types::{error::Error, config_data::ConfigData, price_data::PriceData};
-use extensions::env_balance_extensions::EnvBalanceExtensions;
-use soroban_sdk::{contractimpl, panic_with_error, Address, BytesN, Env, Vec};
+use shared::extensions::{env_extensions::EnvExtensions, i128_extensions::I128Extensions, u64_extensions::U64Extensions};
+use shared::types::{
+    asset_metadata::AssetMetadata, asset_schedule::AssetSchedule, billing_mode::BillingMode, commit_reveal_config::CommitRevealConfig, consumer_limits::ConsumerLimits, error::Error, config_data::ConfigData, data_key::DataKey, endpoint::Endpoint,
+    endpoint_fee::EndpointFee, fee_args::FeeArgs, fee_asset_config::FeeAssetConfig, free_tier::FreeTier, price_callback::PriceCallback, price_data::PriceData, round_data::RoundData, rounding::Rounding,
+    sponsorship::Sponsorship, subscription::Subscription, subscription_plan::SubscriptionPlan,
+    provider_stake::ProviderStake, signed_price::SignedPrice, staking_config::StakingConfig, top_up::TopUp,
+    treasury::Treasury, usage_data::UsageData,
+};
+use extensions::{
+    env_balance_extensions::EnvBalanceExtensions, env_callback_extensions::EnvCallbackExtensions,
+    env_fee_asset_extensions::EnvFeeAssetExtensions,
+    env_fee_extensions::EnvFeeExtensions, env_free_tier_extensions::EnvFreeTierExtensions,
+    env_limits_extensions::EnvLimitsExtensions, env_rewards_extensions::EnvRewardsExtensions,
+    env_commit_extensions::EnvCommitExtensions, env_round_extensions::EnvRoundExtensions,
+    env_signer_extensions::EnvSignerExtensions,
+    env_staking_extensions::EnvStakingExtensions,
+    env_sponsor_extensions::EnvSponsorExtensions,
+    env_subscription_extensions::EnvSubscriptionExtensions,
+    env_top_up_extensions::EnvTopUpExtensions, env_treasury_extensions::EnvTreasuryExtensions,
+    env_usage_extensions::EnvUsageExtensions,
+};
+use soroban_sdk::{
+    contractimpl, panic_with_error, serde::Serialize, Address, Bytes, BytesN, Env, IntoVal, Map,
+    RawVal, Status, Symbol, Vec,
+};
 
 mod token {
     soroban_sdk::contractimport!(file = "../soroban_token_spec.wasm");
@@ -65,67 +87,1388 @@ impl PriceOracleContract {
         e.set_base_fee(fee);
     }
 
-    /// Sets the prices for the assets. Can only be called by the admin.
+    /// Sets the fee multipliers for the given endpoint. Can only be called by the admin.
     /// 
     /// # Arguments
     /// 
     /// * `user` - The admin address.
+    /// * `endpoint` - The endpoint to set the fee for.
+    /// * `fee` - The fee multipliers.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn set_endpoint_fee(e: Env, user: Address, endpoint: Endpoint, fee: EndpointFee) {
+        e.panic_if_not_admin(&user);
+        e.set_endpoint_fee(endpoint, &fee);
+    }
+
+    /// Sets the additional fee charged per call for the given asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `fee` - The fee to set. 0 removes the asset fee.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn set_asset_fee(e: Env, user: Address, asset: Address, fee: i128) {
+        e.panic_if_not_admin(&user);
+        e.set_asset_fee(asset, fee);
+    }
+
+    /// Adds or updates the additional fee asset accepted for deposits. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The fee asset address. The asset must be supported by the oracle to be converted.
+    /// * `config` - The conversion parameters.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the haircut exceeds 100%.
+    pub fn set_fee_asset(e: Env, user: Address, asset: Address, config: FeeAssetConfig) {
+        e.panic_if_not_admin(&user);
+        if config.haircut > 10000 {
+            panic_with_error!(&e, Error::InvalidHaircut);
+        }
+        let mut fee_assets = e.get_fee_assets();
+        fee_assets.set(asset, config);
+        e.set_fee_assets(&fee_assets);
+    }
+
+    /// Removes the additional fee asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The fee asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn remove_fee_asset(e: Env, user: Address, asset: Address) {
+        e.panic_if_not_admin(&user);
+        let mut fee_assets = e.get_fee_assets();
+        fee_assets.remove(asset);
+        e.set_fee_assets(&fee_assets);
+    }
+
+    /// Sets the billing mode that defines which party is billed for the reads. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `mode` - The billing mode.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn set_billing_mode(e: Env, user: Address, mode: BillingMode) {
+        e.panic_if_not_admin(&user);
+        e.set_billing_mode(mode);
+    }
+
+    /// Sets the duration of the usage metering periods. Changing it does not affect the already recorded periods. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `period` - The period duration in seconds.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the period is zero.
+    pub fn set_usage_period(e: Env, user: Address, period: u64) {
+        e.panic_if_not_admin(&user);
+        if period == 0 {
+            panic_with_error!(&e, Error::InvalidUsagePeriod);
+        }
+        e.set_usage_period(period);
+    }
+
+    /// Registers the given price providers. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `providers` - The provider addresses.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if a provider is already registered.
+    pub fn add_providers(e: Env, user: Address, providers: Vec<Address>) {
+        PriceOracle::add_providers(&e, user, providers)
+    }
+
+    /// Removes the given price providers. The removed providers keep the accrued rewards, but stop earning. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `providers` - The provider addresses.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if a provider is not registered.
+    pub fn remove_providers(e: Env, user: Address, providers: Vec<Address>) {
+        PriceOracle::remove_providers(&e, user, providers.clone());
+        for provider in providers.iter() {
+            e.remove_provider_rounds(provider.unwrap());
+        }
+    }
+
+    /// Sets the share of the earned fees distributed to the price providers in proportion to the contributed rounds. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `share` - The share in basis points.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the share exceeds 100%.
+    pub fn set_provider_share(e: Env, user: Address, share: u32) {
+        e.panic_if_not_admin(&user);
+        if share > 10000 {
+            panic_with_error!(&e, Error::InvalidProviderShare);
+        }
+        let mut reward_pool = e.get_reward_pool();
+        reward_pool.share = share;
+        e.set_reward_pool(&reward_pool);
+    }
+
+    /// Sets the number of the provider submissions required to set the round prices. Can only be called by the admin.
+    /// Defaults to the majority of the registered providers.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `quorum` - The number of the submissions.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the quorum is zero.
+    pub fn set_quorum(e: Env, user: Address, quorum: u32) {
+        e.panic_if_not_admin(&user);
+        if quorum == 0 {
+            panic_with_error!(&e, Error::InvalidQuorum);
+        }
+        e.set_quorum(quorum);
+    }
+
+    /// Sets the metadata of the given asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `metadata` - The asset metadata.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, or if the symbol is used by another asset.
+    pub fn set_asset_metadata(e: Env, user: Address, asset: Address, metadata: AssetMetadata) {
+        PriceOracle::set_asset_metadata(&e, user, asset, metadata)
+    }
+
+    /// Sets the update schedule of the given asset. A zero heartbeat updates the asset every round. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `schedule` - The heartbeat and the deviation trigger.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, or if the heartbeat or the deviation is invalid.
+    pub fn set_asset_schedule(e: Env, user: Address, asset: Address, schedule: AssetSchedule) {
+        PriceOracle::set_asset_schedule(&e, user, asset, schedule)
+    }
+
+    /// Sets the price decimals of the given asset. Must be set before the first price update of the asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `decimals` - The number of decimals of the asset prices.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, if the decimals exceed 24, or if the asset already has prices.
+    pub fn set_asset_decimals(e: Env, user: Address, asset: Address, decimals: u32) {
+        PriceOracle::set_asset_decimals(&e, user, asset, decimals)
+    }
+
+    /// Adds the given quote assets with direct price feeds. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `quotes` - The quote assets to add.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the quotes are already added.
+    pub fn add_quotes(e: Env, user: Address, quotes: Vec<Address>) {
+        PriceOracle::add_quotes(&e, user, quotes)
+    }
+
+    /// Sets the prices for the assets in the given quote asset. The cross prices use them instead of the derived ones. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `quote` - The quote asset address.
+    /// * `updates` - The prices to set in the contract decimals. 0 means no price for the asset.
+    /// * `timestamp` - The timestamp of the prices. It is normalized to the resolution.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the quote is not added, or if the prices are invalid.
+    pub fn set_quote_price(e: Env, user: Address, quote: Address, updates: Vec<i128>, timestamp: u64) {
+        PriceOracle::set_quote_price(&e, user, quote, updates, timestamp)
+    }
+
+    /// Sets the prices for the assets. Can only be called by the admin or a registered provider.
+    /// The provider submissions are aggregated to the median of the round once the quorum is reached, the admin prices are set directly.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin or provider address.
     /// * `updates` - The prices to set.
     /// * `timestamp` - The timestamp of the prices.
     /// 
     /// # Panics
     /// 
-    /// Panics if the caller is not the admin, or if the prices are invalid.
-    pub fn set_price(e: Env, user: Address, updates: Vec<i128>, timestamp: u64) {
-        PriceOracle::set_price(&e, user, updates, timestamp)
+    /// Panics if the caller is not the admin or a provider, if the provider prices must be committed and revealed, 
+    /// if the provider stake is insufficient, if the provider timestamp is ahead of the ledger time, if the timestamp is older than the latest round, 
+    /// if the round is finalized, or if the prices are invalid.
+    pub fn set_price(e: Env, user: Address, updates: Vec<i128>, timestamp: u64) {
+        if !e.is_provider(&user) {
+            PriceOracle::set_price(&e, user, updates, timestamp);
+        } else {
+            if e.get_commit_reveal_config().is_some() {
+                panic_with_error!(&e, Error::CommitRevealRequired);
+            }
+            submit_provider_prices(&e, user, updates, timestamp);
+        }
+        invoke_callbacks(&e);
+    }
+
+    /// Sets the windows of the two-phase provider submission. Zero windows disable it. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `config` - The commit and reveal windows in milliseconds.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the windows exceed the resolution.
+    pub fn set_commit_reveal(e: Env, user: Address, config: CommitRevealConfig) {
+        e.panic_if_not_admin(&user);
+        if config.commit_window + config.reveal_window > Constants::RESOLUTION as u64 {
+            panic_with_error!(&e, Error::InvalidCommitRevealConfig);
+        }
+        e.set_commit_reveal_config(&config);
+    }
+
+    /// Commits the hash of the provider prices for the round. Can only be called by a registered provider within the commit window.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `timestamp` - The round timestamp. It is normalized to the resolution.
+    /// * `hash` - The sha256 hash of the serialized prices followed by the salt.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized or not registered, if the provider stake is insufficient, if the timestamp is ahead of the ledger time, or if the commit window is closed.
+    pub fn commit_price(e: Env, provider: Address, timestamp: u64, hash: BytesN<32>) {
+        provider.require_auth();
+        if !e.is_provider(&provider) {
+            panic_with_error!(&e, Error::Unauthorized);
+        }
+        let timestamp = get_round_timestamp(&e, timestamp);
+        let config = e.get_commit_reveal_config();
+        if config.is_none() {
+            panic_with_error!(&e, Error::OutsideWindow);
+        }
+        let now = e.ledger().timestamp() * 1000;
+        if now < timestamp || now >= timestamp + config.unwrap().commit_window {
+            panic_with_error!(&e, Error::OutsideWindow);
+        }
+        e.panic_if_insufficient_stake(provider.clone());
+        e.start_commit_round(timestamp);
+        e.set_commitment(provider, timestamp, &hash);
+    }
+
+    /// Reveals the committed provider prices for the round. Only the prices matching the commitment count toward the round.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `updates` - The committed prices.
+    /// * `salt` - The committed salt.
+    /// * `timestamp` - The round timestamp.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized, if the timestamp is ahead of the ledger time, if there is no commitment, if the reveal window is closed, or if the prices do not match the commitment.
+    pub fn reveal_price(e: Env, provider: Address, updates: Vec<i128>, salt: BytesN<32>, timestamp: u64) {
+        provider.require_auth();
+        let timestamp = get_round_timestamp(&e, timestamp);
+        let config = e.get_commit_reveal_config();
+        if config.is_none() {
+            panic_with_error!(&e, Error::OutsideWindow);
+        }
+        let config = config.unwrap();
+        let now = e.ledger().timestamp() * 1000;
+        let reveal_start = timestamp + config.commit_window;
+        if now < reveal_start || now >= reveal_start + config.reveal_window {
+            panic_with_error!(&e, Error::OutsideWindow);
+        }
+
+        let commitment = e.get_commitment(provider.clone(), timestamp);
+        if commitment.is_none() {
+            panic_with_error!(&e, Error::CommitmentNotFound);
+        }
+        let mut data = updates.clone().serialize(&e);
+        let salt: Bytes = salt.into();
+        data.append(&salt);
+        if e.crypto().sha256(&data) != commitment.unwrap() {
+            panic_with_error!(&e, Error::InvalidReveal);
+        }
+        e.remove_commitment(provider.clone(), timestamp);
+
+        submit_provider_prices(&e, provider, updates, timestamp);
+        invoke_callbacks(&e);
+    }
+
+    /// Returns the windows of the two-phase provider submission.
+    pub fn commit_reveal(e: Env) -> Option<CommitRevealConfig> {
+        e.get_commit_reveal_config()
+    }
+
+    /// Returns the number of rounds the provider committed to, but did not reveal.
+    pub fn missed_reveals(e: Env, provider: Address) -> u32 {
+        e.get_missed_reveals(provider)
+    }
+
+    //end of admin section
+
+    //Balance section
+
+    /// Deposits the given amount of fee asset to the current contract address. Can only be called by the user.
+    /// Deposits in additional fee assets are credited at the current price to the fee asset minus the haircut. 
+    /// The credit can only be spent on fees, it can't be withdrawn. The credit of an account is backed by a single additional fee asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The user address.
+    /// * `account` - The account or contract address to deposit to.
+    /// * `asset` - The fee asset or an accepted additional fee asset to deposit.
+    /// * `amount` - The amount to deposit.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the amount is invalid, or if the fee asset is invalid, or if the conversion price or the token decimals are missing, or if the price is stale, or if the unspent credit is backed by another asset, or if transfer fails.
+    pub fn deposit(e: Env, user: Address, account: Address, asset: Address, amount: i128) {
+        user.require_auth();
+        let credited = receive_fee_tokens(&e, &user, asset.clone(), amount);
+        if asset != fee_asset(&e) {
+            if !e.try_add_credit(account, asset, amount, credited) {
+                panic_with_error!(&e, Error::CreditAssetMismatch);
+            }
+            e.record_credit(credited);
+            return;
+        }
+        e.try_inc_balance(account, credited);
+        e.record_deposit(credited);
+    }
+
+    /// Withdraws the given amount of fee asset from the account balance to the given address. Can only be called by the account.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address to withdraw from.
+    /// * `to` - The address to transfer the funds to.
+    /// * `amount` - The amount to withdraw.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account is not authorized, if the amount is invalid, or if the account does not have enough balance.
+    pub fn withdraw(e: Env, account: Address, to: Address, amount: i128) {
+        account.require_auth();
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidWithdrawAmount);
+        }
+        if !e.try_inc_balance(account, -amount) {
+            panic_with_error!(&e, Error::InsufficientBalance);
+        }
+        e.record_withdrawal(amount);
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
+    }
+
+    /// Sets the limits of paid reads for the account balance. Can only be called by the account.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address.
+    /// * `limits` - The limits to set. All zero values remove the limits.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account is not authorized, or if a per-period limit is set with a zero period.
+    pub fn set_limits(e: Env, account: Address, limits: ConsumerLimits) {
+        account.require_auth();
+        //a zero period never resets, so the per-period limits would become lifetime limits
+        if limits.period == 0 && (limits.calls_per_period > 0 || limits.fees_per_period > 0) {
+            panic_with_error!(&e, Error::InvalidLimits);
+        }
+        e.set_limits(account, &limits);
+    }
+
+    /// Returns the limits of the given account.
+    pub fn limits(e: Env, account: Address) -> Option<ConsumerLimits> {
+        e.get_limits(account)
+    }
+
+    /// Sets the balance threshold below which the `low_bal` event is published. Can only be called by the account.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address.
+    /// * `threshold` - The balance threshold. 0 removes the threshold.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account is not authorized.
+    pub fn set_low_balance(e: Env, account: Address, threshold: i128) {
+        account.require_auth();
+        e.set_low_balance(account, threshold);
+    }
+
+    /// Configures the automatic top-up of the account balance. Can only be called by the account with the consent of the funder.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address.
+    /// * `funder` - The address the fee tokens are pulled from. It must allow the contract to spend them.
+    /// * `amount` - The amount of fee tokens pulled when the balance is insufficient for a read.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account or the funder is not authorized or if the amount is invalid.
+    pub fn set_top_up(e: Env, account: Address, funder: Address, amount: i128) {
+        account.require_auth();
+        //the allowance is given to the contract, so the funder binds it to the account
+        if funder != account {
+            funder.require_auth();
+        }
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidDepositAmount);
+        }
+        e.set_top_up(account, Some(TopUp { funder, amount }));
+    }
+
+    /// Removes the automatic top-up of the account balance. Can only be called by the account.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account is not authorized.
+    pub fn remove_top_up(e: Env, account: Address) {
+        account.require_auth();
+        e.set_top_up(account, None);
+    }
+
+    /// Returns the low balance threshold of the given account.
+    pub fn low_balance(e: Env, account: Address) -> Option<i128> {
+        e.get_low_balance(account)
+    }
+
+    /// Returns the automatic top-up of the given account.
+    pub fn top_up(e: Env, account: Address) -> Option<TopUp> {
+        e.get_top_up(account)
+    }
+
+    /// Returns the balance of the given account.
+    pub fn balance(e: Env, account: Address) -> Option<i128> {
+        e.get_balance(account)
+    }
+
+    /// Returns the credit of the given account from the deposits in additional fee assets.
+    pub fn credit_balance(e: Env, account: Address) -> i128 {
+        e.get_credit_balance(account)
+    }
+
+    /// Returns the usage statement of the given account.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account address.
+    /// * `from` - The start of the range in seconds.
+    /// * `to` - The end of the range in seconds.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the range is empty or covers more than 100 periods.
+    /// 
+    /// # Returns
+    /// 
+    /// The calls by endpoint and the fees paid for each period in the range that has any usage.
+    pub fn usage(e: Env, account: Address, from: u64, to: u64) -> Vec<UsageData> {
+        let usage = e.get_usage_range(account, from, to, 100);
+        if usage.is_none() {
+            panic_with_error!(&e, Error::InvalidUsageRange);
+        }
+        usage.unwrap()
+    }
+
+    /// Returns the fee asset of the contract.
+    pub fn fee_asset(e: Env) -> Address {
+        fee_asset(&e)
+    }
+
+    /// Returns the additional fee assets accepted for deposits.
+    pub fn fee_assets(e: Env) -> Map<Address, FeeAssetConfig> {
+        e.get_fee_assets()
+    }
+
+    /// Returns the amount of the additional fee asset earned from the charged credit and not claimed yet.
+    pub fn reserve(e: Env, asset: Address) -> i128 {
+        e.get_reserve(asset)
+    }
+
+    /// Returns the billing mode of the contract.
+    pub fn billing_mode(e: Env) -> BillingMode {
+        e.get_billing_mode()
+    }
+
+    /// Returns the base fee of the contract.
+    pub fn base_fee(e: Env) -> Option<i128> {
+        e.get_base_fee()
+    }
+
+    /// Returns the fee multipliers of the given endpoint.
+    pub fn endpoint_fee(e: Env, endpoint: Endpoint) -> EndpointFee {
+        e.get_endpoint_fee(endpoint)
+    }
+
+    /// Returns the additional fee charged per call for the given asset.
+    pub fn asset_fee(e: Env, asset: Address) -> Option<i128> {
+        e.get_asset_fee(asset)
+    }
+
+    /// Returns the fee that would be charged for the given endpoint call.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `endpoint` - The endpoint to quote.
+    /// * `args` - The assets and the number of records requested by the call.
+    /// 
+    /// # Returns
+    /// 
+    /// The fee amount in fee asset.
+    pub fn quote_fee(e: Env, endpoint: Endpoint, args: FeeArgs) -> i128 {
+        e.calc_fee(endpoint, &args.assets, args.records)
+    }
+
+    //end of balance section
+
+    //Free tier section
+
+    /// Sets the discount for the given consumers. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `accounts` - The consumer addresses.
+    /// * `discount` - The discount in basis points. 10000 exempts the consumers from fees, 0 removes them from the allowlist.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the discount exceeds 100%.
+    pub fn set_discount(e: Env, user: Address, accounts: Vec<Address>, discount: u32) {
+        e.panic_if_not_admin(&user);
+        if discount > 10000 {
+            panic_with_error!(&e, Error::InvalidDiscount);
+        }
+        for account in accounts.iter() {
+            e.set_discount(account.unwrap(), discount);
+        }
+    }
+
+    /// Sets the free quota granted to every consumer. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `free_tier` - The free quota. 0 calls disables the free tier.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn set_free_tier(e: Env, user: Address, free_tier: FreeTier) {
+        e.panic_if_not_admin(&user);
+        e.set_free_tier(&free_tier);
+    }
+
+    /// Returns the discount of the given consumer in basis points.
+    pub fn discount(e: Env, account: Address) -> Option<u32> {
+        e.get_discount(account)
+    }
+
+    /// Returns the free quota granted to every consumer.
+    pub fn free_tier(e: Env) -> Option<FreeTier> {
+        e.get_free_tier()
+    }
+
+    /// Returns the number of free calls left for the given consumer in the current period.
+    pub fn free_calls(e: Env, account: Address) -> u32 {
+        e.get_free_calls(account)
+    }
+
+    //end of free tier section
+
+    //Sponsorship section
+
+    /// Deposits the given amount of fee asset to the sponsor balance. Can only be called by the user.
+    /// Deposits in additional fee assets are credited to the sponsor credit, which can only be spent on fees.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The user address.
+    /// * `sponsor` - The sponsor address to deposit to.
+    /// * `asset` - The fee asset or an accepted additional fee asset to deposit.
+    /// * `amount` - The amount to deposit.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the amount is invalid, or if the fee asset is invalid, or if the conversion price or the token decimals are missing, or if the price is stale, or if the unspent credit is backed by another asset, or if transfer fails.
+    pub fn deposit_sponsor(e: Env, user: Address, sponsor: Address, asset: Address, amount: i128) {
+        user.require_auth();
+        let credited = receive_fee_tokens(&e, &user, asset.clone(), amount);
+        if asset != fee_asset(&e) {
+            if !e.try_add_sponsor_credit(sponsor, asset, amount, credited) {
+                panic_with_error!(&e, Error::CreditAssetMismatch);
+            }
+            e.record_credit(credited);
+            return;
+        }
+        e.try_inc_sponsor_balance(sponsor, credited);
+        e.record_deposit(credited);
+    }
+
+    /// Withdraws the given amount of fee asset from the sponsor balance to the given address. Can only be called by the sponsor.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sponsor` - The sponsor address.
+    /// * `to` - The address to transfer the funds to.
+    /// * `amount` - The amount to withdraw.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the sponsor is not authorized, if the amount is invalid, or if the sponsor does not have enough balance.
+    pub fn withdraw_sponsor(e: Env, sponsor: Address, to: Address, amount: i128) {
+        sponsor.require_auth();
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidWithdrawAmount);
+        }
+        if !e.try_inc_sponsor_balance(sponsor, -amount) {
+            panic_with_error!(&e, Error::InsufficientBalance);
+        }
+        e.record_withdrawal(amount);
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
+    }
+
+    /// Authorizes the consumers to pay fees from the sponsor balance when their own balance is insufficient. 
+    /// Updates the cap if the consumer is already sponsored by the sponsor. Can only be called by the sponsor, the new consumers must accept the sponsorship.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sponsor` - The sponsor address.
+    /// * `consumers` - The consumer addresses to sponsor.
+    /// * `cap` - The maximum amount each consumer can spend from the sponsor balance. 0 means no cap.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the sponsor or a new consumer is not authorized, or if any consumer is sponsored by another sponsor.
+    pub fn sponsor(e: Env, sponsor: Address, consumers: Vec<Address>, cap: i128) {
+        sponsor.require_auth();
+        for consumer in consumers.iter() {
+            let consumer = consumer.unwrap();
+            let mut sponsorship = Sponsorship {
+                sponsor: sponsor.clone(),
+                cap,
+                spent: 0,
+            };
+            let current = e.get_sponsorship(consumer.clone());
+            if current.is_some() {
+                let current = current.unwrap();
+                if current.sponsor != sponsor {
+                    panic_with_error!(&e, Error::AlreadySponsored);
+                }
+                sponsorship.spent = current.spent;
+            } else {
+                //the consumer can't be bound to a sponsor without its consent
+                consumer.require_auth();
+            }
+            e.set_sponsorship(consumer, &sponsorship);
+        }
+    }
+
+    /// Revokes the sponsorship of the consumers. Can only be called by the sponsor.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sponsor` - The sponsor address.
+    /// * `consumers` - The consumer addresses to revoke.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the sponsor is not authorized, or if any consumer is not sponsored by the sponsor.
+    pub fn revoke_sponsor(e: Env, sponsor: Address, consumers: Vec<Address>) {
+        sponsor.require_auth();
+        for consumer in consumers.iter() {
+            let consumer = consumer.unwrap();
+            let current = e.get_sponsorship(consumer.clone());
+            if current.is_none() || current.unwrap().sponsor != sponsor {
+                panic_with_error!(&e, Error::SponsorshipNotFound);
+            }
+            e.try_delete_data(DataKey::Sponsorship(consumer));
+        }
+    }
+
+    /// Removes the sponsorship of the consumer. Can only be called by the consumer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `consumer` - The consumer address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the consumer is not authorized, or if the consumer is not sponsored.
+    pub fn leave_sponsorship(e: Env, consumer: Address) {
+        consumer.require_auth();
+        if !e.try_delete_data(DataKey::Sponsorship(consumer)) {
+            panic_with_error!(&e, Error::SponsorshipNotFound);
+        }
+    }
+
+    /// Returns the balance of the given sponsor.
+    pub fn sponsor_balance(e: Env, sponsor: Address) -> Option<i128> {
+        e.get_sponsor_balance(sponsor)
+    }
+
+    /// Returns the credit of the given sponsor from the deposits in additional fee assets.
+    pub fn sponsor_credit(e: Env, sponsor: Address) -> i128 {
+        e.get_sponsor_credit(sponsor)
+    }
+
+    /// Returns the sponsorship of the given consumer.
+    pub fn sponsorship(e: Env, consumer: Address) -> Option<Sponsorship> {
+        e.get_sponsorship(consumer)
+    }
+
+    //end of sponsorship section
+
+    //Treasury section
+
+    /// Sets the fee manager allowed to claim the earned fees. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `fee_manager` - The fee manager address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn set_fee_manager(e: Env, user: Address, fee_manager: Address) {
+        e.panic_if_not_admin(&user);
+        e.set_fee_manager(&fee_manager);
+    }
+
+    /// Transfers the given amount of earned fees to the given address. Can only be called by the fee manager.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The fee manager address.
+    /// * `to` - The address to transfer the fees to.
+    /// * `amount` - The amount to claim.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the fee manager, if the amount is invalid, or if the amount exceeds the earned fees.
+    pub fn claim_fees(e: Env, user: Address, to: Address, amount: i128) {
+        e.panic_if_not_fee_manager(&user);
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidClaimAmount);
+        }
+        e.record_claim(amount);
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
+    }
+
+    /// Transfers the given amount of the additional fee asset received from deposits to the given address. 
+    /// The reserves are the tokens backing the credit already charged, the tokens backing the unspent credit can't be claimed. Can only be called by the fee manager.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The fee manager address.
+    /// * `asset` - The additional fee asset address.
+    /// * `to` - The address to transfer the reserve to.
+    /// * `amount` - The amount to claim.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the fee manager, if the amount is invalid, or if the amount exceeds the reserve.
+    pub fn claim_reserve(e: Env, user: Address, asset: Address, to: Address, amount: i128) {
+        e.panic_if_not_fee_manager(&user);
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidClaimAmount);
+        }
+        if !e.try_inc_reserve(asset.clone(), -amount) {
+            panic_with_error!(&e, Error::InsufficientReserve);
+        }
+        let token = token::Client::new(&e, &asset.contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
+    }
+
+    /// Returns the fee manager address. Defaults to the admin.
+    pub fn fee_manager(e: Env) -> Address {
+        e.get_fee_manager()
+    }
+
+    /// Returns the accounting of the fee asset held by the contract.
+    pub fn treasury(e: Env) -> Treasury {
+        e.get_treasury()
+    }
+
+    //end of treasury section
+
+    //Rewards section
+
+    /// Transfers the given amount of rewards to the given address. Can only be called by the provider.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `to` - The address to transfer the rewards to.
+    /// * `amount` - The amount to claim.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized, if the amount is invalid, or if the amount exceeds the provider rewards.
+    pub fn claim_rewards(e: Env, provider: Address, to: Address, amount: i128) {
+        provider.require_auth();
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidClaimAmount);
+        }
+        e.record_reward_claim(provider, amount);
+        e.record_rewards_claim(amount);
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
     }
 
-    //end of admin section
+    /// Returns the registered price providers.
+    pub fn providers(e: Env) -> Vec<Address> {
+        PriceOracle::providers(&e)
+    }
 
-    //Balance section
+    /// Returns the number of the provider submissions required to set the round prices.
+    pub fn quorum(e: Env) -> u32 {
+        e.get_quorum()
+    }
 
-    /// Deposits the given amount of fee asset to the current contract address. Can only be called by the user.
+    /// Returns the share of the earned fees distributed to the price providers in basis points.
+    pub fn provider_share(e: Env) -> u32 {
+        e.get_reward_pool().share
+    }
+
+    /// Returns the unclaimed rewards of the given provider.
+    pub fn rewards(e: Env, provider: Address) -> i128 {
+        e.get_rewards(provider)
+    }
+
+    //end of rewards section
+
+    //Staking section
+
+    /// Sets the staking requirements of the price providers. Can only be called by the admin.
     /// 
     /// # Arguments
     /// 
-    /// * `user` - The user address.
-    /// * `account` - The contract address to deposit to.
-    /// * `asset` - The fee asset to deposit.
-    /// * `amount` - The amount to deposit.
+    /// * `user` - The admin address.
+    /// * `config` - The staking requirements.
     /// 
     /// # Panics
     /// 
-    /// Panics if the amount is invalid, or if the fee asset is invalid, or if transfer fails.
-    pub fn deposit(e: Env, user: Address, account: BytesN<32>, asset: Address, amount: i128) {
-        user.require_auth();
+    /// Panics if the caller is not the admin, or if the deviation or the slash share exceeds 100%.
+    pub fn set_staking_config(e: Env, user: Address, config: StakingConfig) {
+        e.panic_if_not_admin(&user);
+        if config.max_deviation > 10000 || config.slash_share > 10000 || config.min_stake < 0 {
+            panic_with_error!(&e, Error::InvalidStakingConfig);
+        }
+        e.set_staking_config(&config);
+    }
+
+    /// Stakes the given amount of fee asset. Can only be called by the provider.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `amount` - The amount to stake.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized, or if the amount is invalid.
+    pub fn stake(e: Env, provider: Address, amount: i128) {
+        provider.require_auth();
         if amount <= 0 {
-            panic_with_error!(&e, Error::InvalidDepositAmount);
+            panic_with_error!(&e, Error::InvalidStakeAmount);
+        }
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&provider, &e.current_contract_address(), &amount);
+
+        let mut stake = e.get_provider_stake(provider.clone());
+        stake.amount += amount;
+        e.set_provider_stake(provider, &stake);
+        e.record_stake(amount);
+    }
+
+    /// Unbonds the given amount of the stake. The unbonded stake can be withdrawn after the unbonding period and remains slashable until then.
+    /// Unbonding more resets the unbonding period. Can only be called by the provider.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `amount` - The amount to unbond.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized, or if the amount is invalid or exceeds the stake.
+    pub fn unbond(e: Env, provider: Address, amount: i128) {
+        provider.require_auth();
+        let mut stake = e.get_provider_stake(provider.clone());
+        if amount <= 0 || amount > stake.amount {
+            panic_with_error!(&e, Error::InvalidStakeAmount);
         }
-        let fee_asset = fee_asset(&e);
-        if fee_asset != asset {
-            panic_with_error!(&e, Error::InvalidFeeAsset);
+        let config = e.get_staking_config();
+        let mut unbonding_period = 0;
+        if config.is_some() {
+            unbonding_period = config.unwrap().unbonding_period;
         }
-        let token = token::Client::new(&e, &asset.contract_id().unwrap());
-        token.xfer(&user, &e.current_contract_address(), &amount);
-        e.try_inc_balance(account, amount);
+        stake.amount -= amount;
+        stake.unbonding += amount;
+        stake.unbonding_until = e.ledger().timestamp() + unbonding_period;
+        e.set_provider_stake(provider, &stake);
     }
 
-    /// Returns the balance of the given account.
-    pub fn balance(e: Env, account: BytesN<32>) -> Option<i128> {
-        e.get_balance(account)
+    /// Withdraws the unbonded stake to the given address. Can only be called by the provider.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `provider` - The provider address.
+    /// * `to` - The address to transfer the stake to.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the provider is not authorized, if there is no unbonded stake, or if the unbonding period is not over.
+    pub fn withdraw_stake(e: Env, provider: Address, to: Address) {
+        provider.require_auth();
+        let mut stake = e.get_provider_stake(provider.clone());
+        if stake.unbonding == 0 {
+            panic_with_error!(&e, Error::InvalidStakeAmount);
+        }
+        if stake.unbonding_until > e.ledger().timestamp() {
+            panic_with_error!(&e, Error::StakeLocked);
+        }
+        let amount = stake.unbonding;
+        stake.unbonding = 0;
+        e.set_provider_stake(provider, &stake);
+        e.record_stake(-amount);
+
+        let token = token::Client::new(&e, &fee_asset(&e).contract_id().unwrap());
+        token.xfer(&e.current_contract_address(), &to, &amount);
     }
 
-    /// Returns the fee asset of the contract.
-    pub fn fee_asset(e: Env) -> Address {
-        fee_asset(&e)
+    /// Slashes the given amount of the provider stake, including the unbonding stake. The slashed amount is added to the earned fees. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `provider` - The provider address.
+    /// * `amount` - The amount to slash.
+    /// 
+    /// # Returns
+    /// 
+    /// The slashed amount, limited by the provider stake.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the amount is invalid.
+    pub fn slash(e: Env, user: Address, provider: Address, amount: i128) -> i128 {
+        e.panic_if_not_admin(&user);
+        if amount <= 0 {
+            panic_with_error!(&e, Error::InvalidStakeAmount);
+        }
+        e.slash_stake(provider, amount)
     }
 
-    /// Returns the base fee of the contract.
-    pub fn base_fee(e: Env) -> Option<i128> {
-        e.get_base_fee()
+    /// Returns the staking requirements of the price providers.
+    pub fn staking_config(e: Env) -> Option<StakingConfig> {
+        e.get_staking_config()
     }
 
-    //end of balance section
+    /// Returns the stake of the given provider.
+    pub fn provider_stake(e: Env, provider: Address) -> ProviderStake {
+        e.get_provider_stake(provider)
+    }
+
+    //end of staking section
+
+    //Pull section
+
+    /// Registers the ed25519 public keys of the off-chain price signers. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `signers` - The signer public keys.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if a signer is already registered.
+    pub fn add_signers(e: Env, user: Address, signers: Vec<BytesN<32>>) {
+        e.panic_if_not_admin(&user);
+        let mut presented_signers = e.get_signers();
+        for signer in signers.iter() {
+            let signer = signer.unwrap();
+            if presented_signers.first_index_of(&signer).is_some() {
+                panic_with_error!(&e, Error::SignerAlreadyRegistered);
+            }
+            presented_signers.push_back(signer);
+        }
+        e.set_signers(&presented_signers);
+    }
+
+    /// Removes the given price signers. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `signers` - The signer public keys.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if a signer is not registered.
+    pub fn remove_signers(e: Env, user: Address, signers: Vec<BytesN<32>>) {
+        e.panic_if_not_admin(&user);
+        let mut presented_signers = e.get_signers();
+        for signer in signers.iter() {
+            let index = presented_signers.first_index_of(&signer.unwrap());
+            if index.is_none() {
+                panic_with_error!(&e, Error::SignerNotFound);
+            }
+            presented_signers.remove(index.unwrap());
+        }
+        e.set_signers(&presented_signers);
+    }
+
+    /// Verifies the signed price, stores it if it is fresher than the stored one, and returns the freshest pulled price for the asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `payload` - The signed price.
+    /// * `signer` - The public key of the signer.
+    /// * `signature` - The ed25519 signature of the serialized payload.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not authorized, if the signer is not registered, if the signature or the payload is invalid, 
+    /// if the nonce is already used, or if the caller does not have enough balance.
+    /// 
+    /// # Returns
+    /// 
+    /// The freshest pulled price for the asset.
+    pub fn submit_price(
+        e: Env,
+        caller: Address,
+        payload: SignedPrice,
+        signer: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> PriceData {
+        authorize_caller_or_panic(&e, &caller);
+        if e.get_signers().first_index_of(&signer).is_none() {
+            panic_with_error!(&e, Error::SignerNotFound);
+        }
+        //the payload must be issued for this contract, for a supported asset, and not from the future
+        if payload.contract_id != e.current_contract_id()
+            || e.get_assets().first_index_of(&payload.asset).is_none()
+            || payload.timestamp > e.ledger().timestamp() * 1000
+        {
+            panic_with_error!(&e, Error::InvalidSignedPrice);
+        }
+        if payload.nonce <= e.get_signer_nonce(signer.clone()) {
+            panic_with_error!(&e, Error::InvalidNonce);
+        }
+        //panics if the signature is invalid
+        e.crypto().ed25519_verify(&signer, &payload.clone().serialize(&e), &signature);
+        e.set_signer_nonce(signer, payload.nonce);
+
+        let asset = payload.asset;
+        let mut price = e.get_pull_price(asset.clone());
+        if price.is_none() || price.clone().unwrap().timestamp < payload.timestamp {
+            let pulled_price = PriceData {
+                price: payload.price,
+                timestamp: payload.timestamp,
+                decimals: e.get_asset_decimals(asset.clone()),
+            };
+            e.set_pull_price(asset.clone(), &pulled_price);
+            price = Some(pulled_price);
+        }
+        charge_or_panic(&e, caller, Endpoint::PullPrice, Vec::from_array(&e, [asset]), 0);
+        price.unwrap()
+    }
+
+    /// Returns the registered price signers.
+    pub fn signers(e: Env) -> Vec<BytesN<32>> {
+        e.get_signers()
+    }
+
+    /// Returns the last pulled price for the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The last pulled price for the given asset or None if no price was pulled.
+    pub fn pull_price(e: Env, caller: Address, asset: Address) -> Option<PriceData> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = e.get_pull_price(asset.clone());
+        if price.is_none() {
+            return None;
+        }
+        charge_or_panic(&e, caller, Endpoint::PullPrice, Vec::from_array(&e, [asset]), 0);
+        price
+    }
+
+    //end of pull section
+
+    //Callback section
+
+    /// Registers the callback invoked on the asset price updates. Replaces the previous callback of the consumer for the asset. 
+    /// The callback is invoked with the asset address and the price data, and is charged for each invocation. Can only be called by the consumer contract.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `consumer` - The consumer contract address.
+    /// * `asset` - The asset address.
+    /// * `function` - The consumer function to invoke.
+    /// * `deviation` - The price deviation that triggers the callback in basis points. 0 disables the trigger.
+    /// * `heartbeat` - The time after the last invocation that triggers the callback in milliseconds. 0 disables the trigger.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the consumer is not authorized or is not a contract, if there is no trigger, if the deviation exceeds 100%, or if the asset has the maximum number of callbacks.
+    pub fn register_callback(
+        e: Env,
+        consumer: Address,
+        asset: Address,
+        function: Symbol,
+        deviation: u32,
+        heartbeat: u64,
+    ) {
+        consumer.require_auth();
+        let contract_id = consumer.contract_id();
+        if contract_id.is_none() {
+            panic_with_error!(&e, Error::Unauthorized);
+        }
+        let contract_id = contract_id.unwrap();
+
+        let mut callbacks = e.get_callbacks(asset.clone());
+        let index = get_callback_index(&callbacks, &contract_id);
+        if index.is_some() {
+            callbacks.remove(index.unwrap());
+        }
+        if (deviation == 0 && heartbeat == 0) || deviation > 10000 || callbacks.len() >= Limits::MAX_CALLBACKS_PER_ASSET {
+            panic_with_error!(&e, Error::InvalidCallback);
+        }
+        callbacks.push_back(PriceCallback {
+            contract_id,
+            function,
+            deviation,
+            heartbeat,
+            last_price: 0,
+            last_timestamp: 0,
+        });
+        e.set_callbacks(asset, &callbacks);
+    }
+
+    /// Removes the callback of the consumer for the asset. Can only be called by the consumer contract.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `consumer` - The consumer contract address.
+    /// * `asset` - The asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the consumer is not authorized, or if the callback is not registered.
+    pub fn remove_callback(e: Env, consumer: Address, asset: Address) {
+        consumer.require_auth();
+        let mut callbacks = e.get_callbacks(asset.clone());
+        let contract_id = consumer.contract_id();
+        if contract_id.is_none() {
+            panic_with_error!(&e, Error::CallbackNotFound);
+        }
+        let index = get_callback_index(&callbacks, &contract_id.unwrap());
+        if index.is_none() {
+            panic_with_error!(&e, Error::CallbackNotFound);
+        }
+        callbacks.remove(index.unwrap());
+        e.set_callbacks(asset, &callbacks);
+    }
+
+    /// Returns the callbacks registered for the asset.
+    pub fn callbacks(e: Env, asset: Address) -> Vec<PriceCallback> {
+        e.get_callbacks(asset)
+    }
+
+    //end of callback section
+
+    //Subscription section
+
+    /// Sets the subscription plan with the given id. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `plan_id` - The plan id.
+    /// * `plan` - The plan parameters.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the price is negative or the duration is zero.
+    pub fn set_plan(e: Env, user: Address, plan_id: u32, plan: SubscriptionPlan) {
+        e.panic_if_not_admin(&user);
+        if plan.price < 0 || plan.duration == 0 {
+            panic_with_error!(&e, Error::InvalidPlan);
+        }
+        e.set_plan(plan_id, &plan);
+    }
+
+    /// Removes the subscription plan with the given id. Active subscriptions are not affected. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `plan_id` - The plan id.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin.
+    pub fn remove_plan(e: Env, user: Address, plan_id: u32) {
+        e.panic_if_not_admin(&user);
+        e.try_delete_data(DataKey::SubscriptionPlan(plan_id));
+    }
+
+    /// Returns the subscription plan with the given id.
+    pub fn plan(e: Env, plan_id: u32) -> Option<SubscriptionPlan> {
+        e.get_plan(plan_id)
+    }
+
+    /// Subscribes the account to the given plan. The plan price is charged from the account balance. 
+    /// If the account already has an active subscription for the same plan, the subscription is extended.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `account` - The account or contract address to subscribe.
+    /// * `plan_id` - The plan id.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the account is not authorized, if the plan does not exist, if the account has an active subscription for another plan, 
+    /// or if the account does not have enough balance.
+    pub fn subscribe(e: Env, account: Address, plan_id: u32) {
+        account.require_auth();
+
+        let plan = e.get_plan(plan_id);
+        if plan.is_none() {
+            panic_with_error!(&e, Error::PlanNotFound);
+        }
+        let plan = plan.unwrap();
+
+        let mut subscription = Subscription {
+            plan_id,
+            expiration: e.ledger().timestamp(),
+            max_calls: plan.max_calls,
+            calls: 0,
+            assets: plan.assets,
+        };
+
+        let current = e.get_subscription(account.clone());
+        if current.is_some() {
+            let current = current.unwrap();
+            if current.expiration > subscription.expiration {
+                if current.plan_id != plan_id {
+                    panic_with_error!(&e, Error::SubscriptionAlreadyActive);
+                }
+                //extend the active subscription
+                subscription.expiration = current.expiration;
+                subscription.calls = current.calls;
+                if current.max_calls == 0 || plan.max_calls == 0 {
+                    subscription.max_calls = 0;
+                } else {
+                    subscription.max_calls = current.max_calls + plan.max_calls;
+                }
+            }
+        }
+        subscription.expiration += plan.duration;
+
+        if !e.try_charge(account.clone(), plan.price) {
+            panic_with_error!(&e, Error::InsufficientBalance);
+        }
+        e.set_subscription(account, &subscription);
+    }
+
+    /// Returns the active subscription of the given account.
+    pub fn subscription(e: Env, account: Address) -> Option<Subscription> {
+        let subscription = e.get_subscription(account);
+        if subscription.is_none() {
+            return None;
+        }
+        let subscription = subscription.unwrap();
+        if subscription.expiration <= e.ledger().timestamp() {
+            return None;
+        }
+        Some(subscription)
+    }
+
+    //end of subscription section
 
     /// Returns the contract admin address.
     /// 
@@ -145,7 +1488,7 @@ impl PriceOracleContract {
         PriceOracle::base(&e)
     }
 
-    /// Returns the number of decimals for the prices.
+    /// Returns the number of decimals for the cross prices. It is also the default number of decimals for the asset prices.
     /// 
     /// # Returns
     /// 
@@ -154,6 +1497,19 @@ impl PriceOracleContract {
         PriceOracle::decimals(&e)
     }
 
+    /// Returns the number of decimals for the prices of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The number of decimals. Defaults to the contract decimals.
+    pub fn asset_decimals(e: Env, asset: Address) -> u32 {
+        PriceOracle::asset_decimals(&e, asset)
+    }
+
     /// Returns the prices resolution.
     /// 
     /// # Returns
@@ -167,18 +1523,66 @@ impl PriceOracleContract {
     /// 
     /// # Returns
     /// 
-    /// The retention period.
-    pub fn period(e: Env) -> Option<u64> {
-        PriceOracle::period(&e)
+    /// The retention period.
+    pub fn period(e: Env) -> Option<u64> {
+        PriceOracle::period(&e)
+    }
+
+    /// Returns the assets supported by the contract.
+    /// 
+    /// # Returns
+    /// 
+    /// The assets supported by the contract or None if no assets are supported.
+    pub fn assets(e: Env) -> Option<Vec<Address>> {
+        PriceOracle::assets(&e)
+    }
+
+    /// Returns the quote assets with direct price feeds.
+    /// 
+    /// # Returns
+    /// 
+    /// The quote assets.
+    pub fn quotes(e: Env) -> Vec<Address> {
+        PriceOracle::quotes(&e)
+    }
+
+    /// Returns the metadata of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset metadata or None if the metadata is not set.
+    pub fn asset_metadata(e: Env, asset: Address) -> Option<AssetMetadata> {
+        PriceOracle::asset_metadata(&e, asset)
+    }
+
+    /// Returns the update schedule of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset schedule or None if the asset is updated every round.
+    pub fn asset_schedule(e: Env, asset: Address) -> Option<AssetSchedule> {
+        PriceOracle::asset_schedule(&e, asset)
     }
 
-    /// Returns the assets supported by the contract.
+    /// Returns the asset with the given ticker symbol.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `symbol` - The ticker symbol.
     /// 
     /// # Returns
     /// 
-    /// The assets supported by the contract or None if no assets are supported.
-    pub fn assets(e: Env) -> Option<Vec<Address>> {
-        PriceOracle::assets(&e)
+    /// The asset address or None if no asset has the symbol.
+    pub fn asset_by_symbol(e: Env, symbol: Symbol) -> Option<Address> {
+        PriceOracle::asset_by_symbol(&e, symbol)
     }
 
 
@@ -186,23 +1590,24 @@ impl PriceOracleContract {
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `asset` - The asset address.
     /// * `timestamp` - The timestamp.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
     /// 
     /// # Returns
     /// 
     /// The prices for the given asset at the given timestamp or None if the asset is not supported, or if the timestamp is invalid. 
-    pub fn price(e: Env, asset: Address, timestamp: u64) -> Option<PriceData> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, 1);
-        let price = PriceOracle::price(&e, asset, timestamp);
+    pub fn price(e: Env, caller: Address, asset: Address, timestamp: u64) -> Option<PriceData> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::price(&e, asset.clone(), timestamp);
         if price.is_none() {
             return None;
         }
+        charge_or_panic(&e, caller, Endpoint::Price, Vec::from_array(&e, [asset]), 0);
         price
     }
 
@@ -210,52 +1615,104 @@ impl PriceOracleContract {
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `asset` - The asset address.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
     /// 
     /// # Returns
     /// 
     /// The last price for the given asset or None if the asset is not supported.
-    pub fn lastprice(e: Env, asset: Address) -> Option<PriceData> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, 1);
-        let price = PriceOracle::lastprice(&e, asset);
+    pub fn lastprice(e: Env, caller: Address, asset: Address) -> Option<PriceData> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::lastprice(&e, asset.clone());
         if price.is_none() {
             return None;
         }
+        charge_or_panic(&e, caller, Endpoint::LastPrice, Vec::from_array(&e, [asset]), 0);
         price
     }
 
+    /// Returns the data of the latest round for the given asset. Charged as the last price read.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the round data is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The latest round data for the given asset or None if the asset has no price in the latest round.
+    pub fn latest_round_data(e: Env, caller: Address, asset: Address) -> Option<RoundData> {
+        authorize_caller_or_panic(&e, &caller);
+        let round_data = PriceOracle::latest_round_data(&e, asset.clone());
+        if round_data.is_none() {
+            return None;
+        }
+        charge_or_panic(&e, caller, Endpoint::LastPrice, Vec::from_array(&e, [asset]), 0);
+        round_data
+    }
+
+    /// Returns the data of the given round for the given asset. Charged as the price read.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// * `round_id` - The round id.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the round data is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The round data for the given asset or None if the round is unknown or expired, or if the asset has no price in the round.
+    pub fn round_data(e: Env, caller: Address, asset: Address, round_id: u64) -> Option<RoundData> {
+        authorize_caller_or_panic(&e, &caller);
+        let round_data = PriceOracle::round_data(&e, asset.clone(), round_id);
+        if round_data.is_none() {
+            return None;
+        }
+        charge_or_panic(&e, caller, Endpoint::Price, Vec::from_array(&e, [asset]), 0);
+        round_data
+    }
+
     /// Returns the cross price for the given assets at the given timestamp.
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `base_asset` - The base asset address.
     /// * `quote_asset` - The quote asset address.
     /// * `timestamp` - The timestamp.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
     /// 
     /// # Returns
     /// 
     /// The cross price for the given assets at the given timestamp or None if the assets are not supported, or if the timestamp is invalid.
     pub fn x_price(
         e: Env,
+        caller: Address,
         base_asset: Address,
         quote_asset: Address,
         timestamp: u64,
     ) -> Option<PriceData> {        
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, 2);
-        let price = PriceOracle::x_price(&e, base_asset, quote_asset, timestamp);
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::x_price(&e, base_asset.clone(), quote_asset.clone(), timestamp);
         if price.is_none() {
             return None;
         }
+        charge_or_panic(&e, caller, Endpoint::XPrice, Vec::from_array(&e, [base_asset, quote_asset]), 0);
         price
     }
 
@@ -263,23 +1720,24 @@ impl PriceOracleContract {
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `base_asset` - The base asset address.
     /// * `quote_asset` - The quote asset address.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
     /// 
     /// # Returns
     /// 
     /// The last cross price for the given assets or None if the assets are not supported.
-    pub fn x_last_price(e: Env, base_asset: Address, quote_asset: Address) -> Option<PriceData> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, 2);
-        let price = PriceOracle::x_last_price(&e, base_asset, quote_asset);
+    pub fn x_last_price(e: Env, caller: Address, base_asset: Address, quote_asset: Address) -> Option<PriceData> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::x_last_price(&e, base_asset.clone(), quote_asset.clone());
         if price.is_none() {
             return None;
         }
+        charge_or_panic(&e, caller, Endpoint::XLastPrice, Vec::from_array(&e, [base_asset, quote_asset]), 0);
         price
     }
 
@@ -287,101 +1745,398 @@ impl PriceOracleContract {
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `asset` - The asset address.
     /// * `records` - The number of records to return.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per returned record.
     /// 
     /// # Returns
     /// 
     /// The prices for the given asset or None if the asset is not supported. If there are fewer records than requested, the returned vector will be shorter.
-    pub fn prices(e: Env, asset: Address, records: u32) -> Option<Vec<PriceData>> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, records); //TODO: check price multiplier
-        let price =  PriceOracle::prices(&e, asset, records);
-        if price.is_none() {
+    pub fn prices(e: Env, caller: Address, asset: Address, records: u32) -> Option<Vec<PriceData>> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::prices(&e, asset.clone(), records);
+        if prices.is_none() {
             return None;
         }
-        price
+        let prices = prices.unwrap();
+        charge_or_panic(&e, caller, Endpoint::Prices, Vec::from_array(&e, [asset]), prices.len());
+        Some(prices)
     }
 
     /// Returns the stack of cross prices for the given assets.
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `base_asset` - The base asset address.
     /// * `quote_asset` - The quote asset address.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per returned record.
     /// 
     /// # Returns
     /// 
     /// The cross prices for the given assets or None if the assets are not supported. If there are fewer records than requested, the returned vector will be shorter.
     pub fn x_prices(
         e: Env,
+        caller: Address,
         base_asset: Address,
         quote_asset: Address,
         records: u32,
     ) -> Option<Vec<PriceData>> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, records * 2);//TODO: check price multiplier
-        let prices = PriceOracle::x_prices(&e, base_asset, quote_asset, records);
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::x_prices(&e, base_asset.clone(), quote_asset.clone(), records);
         if prices.is_none() {
             return None;
         }
-        prices
+        let prices = prices.unwrap();
+        charge_or_panic(&e, caller, Endpoint::XPrices, Vec::from_array(&e, [base_asset, quote_asset]), prices.len());
+        Some(prices)
     }
 
     /// Returns the time-weighted average price for the given asset over the given number of records.
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `asset` - The asset address.
     /// * `records` - The number of records to use.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
     /// 
     /// # Returns
     /// 
     /// The time-weighted average price for the given asset over the given number of records or None if the asset is not supported.
-    pub fn twap(e: Env, asset: Address, records: u32) -> Option<i128> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, records);
-        let prices = PriceOracle::twap(&e, asset, records);
+    pub fn twap(e: Env, caller: Address, asset: Address, records: u32) -> Option<i128> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::prices(&e, asset.clone(), records);
         if prices.is_none() {
             return None;
         }
-        prices
+        let prices = prices.unwrap();
+        charge_or_panic(&e, caller, Endpoint::Twap, Vec::from_array(&e, [asset]), prices.len());
+        Some(PriceOracle::average_price(&prices))
     }
 
     /// Returns the time-weighted average cross price for the given assets over the given number of records.
     /// 
     /// # Arguments
     /// 
+    /// * `caller` - The address billed for the read.
     /// * `base_asset` - The base asset address.
     /// * `quote_asset` - The quote asset address.
     /// 
     /// # Panics
     /// 
-    /// If invoker is not authorized, or if the invoker does not have enough balance.
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
     /// 
     /// # Returns
     /// 
     /// The time-weighted average cross price for the given assets over the given number of records or None if the assets are not supported.
-    pub fn x_twap(e: Env, base_asset: Address, quote_asset: Address, records: u32) -> Option<i128> {
-        let invoker = get_invoker_or_panic(&e);
-        charge_or_panic(&e, invoker, records);
-        let prices = PriceOracle::x_twap(&e, base_asset, quote_asset, records);
+    pub fn x_twap(e: Env, caller: Address, base_asset: Address, quote_asset: Address, records: u32) -> Option<i128> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::x_prices(&e, base_asset.clone(), quote_asset.clone(), records);
         if prices.is_none() {
             return None;
         }
-        prices
+        let prices = prices.unwrap();
+        charge_or_panic(&e, caller, Endpoint::XTwap, Vec::from_array(&e, [base_asset, quote_asset]), prices.len());
+        Some(PriceOracle::average_price(&prices))
+    }
+
+    /// Converts the amount of the from asset to the equivalent amount of the to asset at the last prices.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `from_asset` - The asset address to convert from.
+    /// * `to_asset` - The asset address to convert to.
+    /// * `amount` - The amount in the from asset token decimals.
+    /// * `rounding` - The rounding direction.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, if the caller does not have enough balance, or if the converted amount overflows. The fee is charged only if the amount is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The amount in the to asset token decimals or None if there are no prices or metadata for the assets.
+    pub fn convert(
+        e: Env,
+        caller: Address,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        rounding: Rounding,
+    ) -> Option<i128> {
+        authorize_caller_or_panic(&e, &caller);
+        let result = PriceOracle::convert(&e, from_asset.clone(), to_asset.clone(), amount, rounding);
+        if result.is_none() {
+            return None;
+        }
+        charge_or_panic(&e, caller, Endpoint::Convert, Vec::from_array(&e, [from_asset, to_asset]), 0);
+        result
+    }
+
+    /// Converts the amount of the from asset to the equivalent amount of the to asset at the prices of the given timestamp.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `from_asset` - The asset address to convert from.
+    /// * `to_asset` - The asset address to convert to.
+    /// * `amount` - The amount in the from asset token decimals.
+    /// * `rounding` - The rounding direction.
+    /// * `timestamp` - The timestamp of the prices.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, if the caller does not have enough balance, or if the converted amount overflows. The fee is charged only if the amount is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The amount in the to asset token decimals or None if there are no prices or metadata for the assets.
+    pub fn convert_at(
+        e: Env,
+        caller: Address,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        rounding: Rounding,
+        timestamp: u64,
+    ) -> Option<i128> {
+        authorize_caller_or_panic(&e, &caller);
+        let result = PriceOracle::convert_at(&e, from_asset.clone(), to_asset.clone(), amount, rounding, timestamp);
+        if result.is_none() {
+            return None;
+        }
+        charge_or_panic(&e, caller, Endpoint::Convert, Vec::from_array(&e, [from_asset, to_asset]), 0);
+        result
+    }
+
+    //checked reads, the try_ prefix is taken by the generated client
+
+    /// Returns the price for the given asset at the given timestamp. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// * `timestamp` - The timestamp.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The price for the given asset at the given timestamp, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_price(
+        e: Env,
+        caller: Address,
+        asset: Address,
+        timestamp: u64,
+    ) -> Result<PriceData, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::try_price(&e, asset.clone(), timestamp)?;
+        charge_or_panic(&e, caller, Endpoint::Price, Vec::from_array(&e, [asset]), 0);
+        Ok(price)
+    }
+
+    /// Returns the last price for the given asset. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The last price for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_lastprice(e: Env, caller: Address, asset: Address) -> Result<PriceData, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::try_lastprice(&e, asset.clone())?;
+        charge_or_panic(&e, caller, Endpoint::LastPrice, Vec::from_array(&e, [asset]), 0);
+        Ok(price)
+    }
+
+    /// Returns the cross price for the given assets at the given timestamp. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `timestamp` - The timestamp.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The cross price for the given assets at the given timestamp, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_price(
+        e: Env,
+        caller: Address,
+        base_asset: Address,
+        quote_asset: Address,
+        timestamp: u64,
+    ) -> Result<PriceData, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::try_x_price(&e, base_asset.clone(), quote_asset.clone(), timestamp)?;
+        charge_or_panic(&e, caller, Endpoint::XPrice, Vec::from_array(&e, [base_asset, quote_asset]), 0);
+        Ok(price)
+    }
+
+    /// Returns the last cross price for the given assets. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged only if the price is returned.
+    /// 
+    /// # Returns
+    /// 
+    /// The last cross price for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_last_price(
+        e: Env,
+        caller: Address,
+        base_asset: Address,
+        quote_asset: Address,
+    ) -> Result<PriceData, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let price = PriceOracle::try_x_last_price(&e, base_asset.clone(), quote_asset.clone())?;
+        charge_or_panic(&e, caller, Endpoint::XLastPrice, Vec::from_array(&e, [base_asset, quote_asset]), 0);
+        Ok(price)
+    }
+
+    /// Returns the last N prices for the given asset. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// * `records` - The number of records to return.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
+    /// 
+    /// # Returns
+    /// 
+    /// The prices for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_prices(
+        e: Env,
+        caller: Address,
+        asset: Address,
+        records: u32,
+    ) -> Result<Vec<PriceData>, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::try_prices(&e, asset.clone(), records)?;
+        charge_or_panic(&e, caller, Endpoint::Prices, Vec::from_array(&e, [asset]), prices.len());
+        Ok(prices)
+    }
+
+    /// Returns the last N cross prices for the given assets. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `records` - The number of records to return.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
+    /// 
+    /// # Returns
+    /// 
+    /// The cross prices for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_prices(
+        e: Env,
+        caller: Address,
+        base_asset: Address,
+        quote_asset: Address,
+        records: u32,
+    ) -> Result<Vec<PriceData>, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::try_x_prices(&e, base_asset.clone(), quote_asset.clone(), records)?;
+        charge_or_panic(&e, caller, Endpoint::XPrices, Vec::from_array(&e, [base_asset, quote_asset]), prices.len());
+        Ok(prices)
+    }
+
+    /// Returns the time-weighted average price for the given asset over the given number of records. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `asset` - The asset address.
+    /// * `records` - The number of records to use.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
+    /// 
+    /// # Returns
+    /// 
+    /// The time-weighted average price for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_twap(
+        e: Env,
+        caller: Address,
+        asset: Address,
+        records: u32,
+    ) -> Result<i128, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::try_prices(&e, asset.clone(), records)?;
+        charge_or_panic(&e, caller, Endpoint::Twap, Vec::from_array(&e, [asset]), prices.len());
+        Ok(PriceOracle::average_price(&prices))
+    }
+
+    /// Returns the time-weighted average cross price for the given assets over the given number of records. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `caller` - The address billed for the read.
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `records` - The number of records to use.
+    /// 
+    /// # Panics
+    /// 
+    /// If the caller is not authorized, or if the caller does not have enough balance. The fee is charged per used record.
+    /// 
+    /// # Returns
+    /// 
+    /// The time-weighted average cross price for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_twap(
+        e: Env,
+        caller: Address,
+        base_asset: Address,
+        quote_asset: Address,
+        records: u32,
+    ) -> Result<i128, Error> {
+        authorize_caller_or_panic(&e, &caller);
+        let prices = PriceOracle::try_x_prices(&e, base_asset.clone(), quote_asset.clone(), records)?;
+        charge_or_panic(&e, caller, Endpoint::XTwap, Vec::from_array(&e, [base_asset, quote_asset]), prices.len());
+        Ok(PriceOracle::average_price(&prices))
     }
 }
 
@@ -390,18 +2145,216 @@ fn fee_asset(e: &Env) -> Address {
     Address::from_contract_id(&e, &bytes)
 }
 
-fn get_invoker_or_panic(e: &Env) -> BytesN<32> {
+fn receive_fee_tokens(e: &Env, user: &Address, asset: Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        panic_with_error!(&e, Error::InvalidDepositAmount);
+    }
+    let mut credited = amount;
+    if asset != fee_asset(e) {
+        credited = convert_fee_tokens(e, asset.clone(), amount);
+    }
+    let token = token::Client::new(&e, &asset.contract_id().unwrap());
+    token.xfer(user, &e.current_contract_address(), &amount);
+    credited
+}
+
+fn convert_fee_tokens(e: &Env, asset: Address, amount: i128) -> i128 {
+    let config = e.get_fee_asset_config(asset.clone());
+    if config.is_none() {
+        panic_with_error!(&e, Error::InvalidFeeAsset);
+    }
+    let config = config.unwrap();
+
+    //the conversion uses the last prices, the age is checked for the price of each asset, price timestamps are in milliseconds
+    let last_timestamp = e.get_last_timestamp().unwrap_or(0);
+    let now = e.ledger().timestamp() * 1000;
+    let base = PriceOracle::base(e);
+    for price_asset in [asset.clone(), fee_asset(e)] {
+        if price_asset == base {
+            continue;
+        }
+        //the scheduled assets may be priced in an earlier round
+        let price_timestamp = e.get_price_timestamp(price_asset, last_timestamp).unwrap_or(last_timestamp);
+        if price_timestamp + config.max_price_age * 1000 < now {
+            panic_with_error!(&e, Error::StalePrice);
+        }
+    }
+
+    //the amounts are rescaled with the token decimals from the asset metadata
+    let converted = PriceOracle::convert(e, asset, fee_asset(e), amount, Rounding::Floor);
+    if converted.is_none() {
+        panic_with_error!(&e, Error::NoConversionPrice);
+    }
+    converted.unwrap() * (10000 - config.haircut as i128) / 10000
+}
+
+fn authorize_caller_or_panic(e: &Env, caller: &Address) {
+    caller.require_auth();
+    if e.get_billing_mode() == BillingMode::Originator {
+        return;
+    }
+    //the contract invoking the oracle directly should pay for the read
     let invoker = e.invoker();
-    if invoker.is_none() {
+    if invoker.is_some() && &Address::from_contract_id(e, &invoker.unwrap()) != caller {
         panic_with_error!(e, Error::Unauthorized)
     }
-    invoker.unwrap()
 }
 
-fn charge_or_panic(e: &Env, account: BytesN<32>, multiplier: u32) {
-    let base_fee = e.get_base_fee().unwrap_or_else(||0);
-    let amount = -(base_fee * multiplier as i128);
-    if !e.try_inc_balance(account, amount) { 
-        panic_with_error!(&e, Error::InsufficientBalance) 
+fn invoke_callbacks(e: &Env) {
+    //the invocations are counted per round, the round is updated by every provider submission
+    let round = e.get_last_timestamp().unwrap_or(0);
+    let prev_invoked = e.get_round_invocations(round);
+    let mut invoked = prev_invoked;
+    for asset in e.get_assets().iter() {
+        if invoked >= Limits::MAX_CALLBACKS_PER_ROUND {
+            break;
+        }
+        let asset = asset.unwrap();
+        let mut callbacks = e.get_callbacks(asset.clone());
+        if callbacks.len() == 0 {
+            continue;
+        }
+        let price = PriceOracle::lastprice(e, asset.clone());
+        if price.is_none() {
+            continue;
+        }
+        let price = price.unwrap();
+        for i in 0..callbacks.len() {
+            //the invocations are bounded per round, the rest is triggered by the next round
+            if invoked >= Limits::MAX_CALLBACKS_PER_ROUND {
+                break;
+            }
+            let mut callback = callbacks.get_unchecked(i).unwrap();
+            if !is_callback_triggered(&callback, &price) {
+                continue;
+            }
+            let consumer = Address::from_contract_id(e, &callback.contract_id);
+            let fee = e.calc_fee(Endpoint::Callback, &Vec::from_array(e, [asset.clone()]), 0);
+            if !try_charge_account(e, consumer.clone(), fee) {
+                continue;
+            }
+            e.record_usage(consumer, Endpoint::Callback, fee);
+
+            //the failed callback is isolated and does not block the update
+            let args: Vec<RawVal> = (asset.clone(), price.clone()).into_val(e);
+            let _ = e.try_invoke_contract::<(), Status>(&callback.contract_id, &callback.function, args);
+
+            callback.last_price = price.price;
+            callback.last_timestamp = price.timestamp;
+            callbacks.set(i, callback);
+            invoked += 1;
+        }
+        e.set_callbacks(asset, &callbacks);
+    }
+    if invoked > prev_invoked {
+        e.set_round_invocations(round, invoked);
+    }
+}
+
+fn is_callback_triggered(callback: &PriceCallback, price: &PriceData) -> bool {
+    if price.timestamp <= callback.last_timestamp {
+        return false;
+    }
+    if callback.last_timestamp == 0 {
+        return true;
+    }
+    if callback.heartbeat > 0 && price.timestamp - callback.last_timestamp >= callback.heartbeat {
+        return true;
+    }
+    price.price.is_deviated(callback.last_price, callback.deviation)
+}
+
+fn get_callback_index(callbacks: &Vec<PriceCallback>, contract_id: &BytesN<32>) -> Option<u32> {
+    for i in 0..callbacks.len() {
+        if &callbacks.get_unchecked(i).unwrap().contract_id == contract_id {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn submit_provider_prices(e: &Env, provider: Address, updates: Vec<i128>, timestamp: u64) {
+    e.panic_if_insufficient_stake(provider.clone());
+    let timestamp = get_round_timestamp(e, timestamp);
+    let is_first_submission = !e.has_submission(provider.clone(), timestamp);
+    let medians = e.submit_round(provider.clone(), updates, timestamp);
+    if medians.is_some() {
+        PriceOracle::set_price(&e, provider.clone(), medians.unwrap(), timestamp);
+    }
+    //only the registered providers earn the revenue share, once per round
+    if is_first_submission {
+        e.record_round(provider);
+    }
+}
+
+//returns the round of the provider timestamp, the rounds ahead of the ledger time are rejected
+fn get_round_timestamp(e: &Env, timestamp: u64) -> u64 {
+    let resolution = Constants::RESOLUTION as u64;
+    let timestamp = timestamp.get_normalized_timestamp(resolution);
+    if timestamp > e.ledger().timestamp() * 1000 + resolution {
+        panic_with_error!(&e, Error::InvalidTimestamp);
+    }
+    timestamp
+}
+
+fn charge_or_panic(e: &Env, account: Address, endpoint: Endpoint, assets: Vec<Address>, records: u32) {
+    let amount = get_fee(e, account.clone(), endpoint, &assets, records);
+    e.panic_if_limits_exceeded(account.clone(), amount);
+    if amount > 0 && !try_charge_account(e, account.clone(), amount) {
+        panic_with_error!(&e, Error::InsufficientBalance)
+    }
+    e.record_usage(account, endpoint, amount);
+}
+
+fn try_charge_account(e: &Env, account: Address, amount: i128) -> bool {
+    let prev_balance = e.get_balance(account.clone()).unwrap_or(0);
+    //fall back to the sponsor balance and then to the automatic top-up if the account balance is insufficient
+    if !e.try_charge(account.clone(), amount)
+        && !e.try_charge_sponsor(account.clone(), amount)
+        && !(try_top_up(e, account.clone()) && e.try_charge(account.clone(), amount))
+    {
+        return false;
+    }
+    e.publish_if_low_balance(account, prev_balance);
+    true
+}
+
+fn try_top_up(e: &Env, account: Address) -> bool {
+    let top_up = e.get_top_up(account.clone());
+    if top_up.is_none() {
+        return false;
     }
-}
\ No newline at end of file
+    let top_up = top_up.unwrap();
+    //the funder must allow the contract to spend the fee tokens
+    let current_contract = e.current_contract_address();
+    let token = token::Client::new(&e, &fee_asset(e).contract_id().unwrap());
+    if token
+        .try_xfer_from(&current_contract, &top_up.funder, &current_contract, &top_up.amount)
+        .is_err()
+    {
+        return false;
+    }
+    e.try_inc_balance(account, top_up.amount);
+    e.record_deposit(top_up.amount);
+    true
+}
+
+fn get_fee(e: &Env, account: Address, endpoint: Endpoint, assets: &Vec<Address>, records: u32) -> i128 {
+    let mut amount = e.calc_fee(endpoint, assets, records);
+    let discount = e.get_discount(account.clone());
+    if discount.is_some() {
+        amount = amount * (10000 - discount.unwrap() as i128) / 10000;
+    }
+    //the exempted reads don't use the subscription or the free quota
+    if amount == 0 {
+        return 0;
+    }
+    //reads covered by an active subscription or the free quota are not charged
+    if e.try_use_subscription(account.clone(), assets) {
+        return 0;
+    }
+    if e.try_use_free_call(account) {
+        return 0;
+    }
+    amount
+}
@@ -3,7 +3,8 @@ extern crate std;
 extern crate alloc;
 
 use alloc::rc::Rc;
-use soroban_sdk::{testutils::Address as _, xdr, Address, BytesN, Env, Symbol, TryIntoVal};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, xdr, Address, Bytes, BytesN, Env, Symbol, TryIntoVal};
 
 use shared::{constants::Constants, extensions::u64_extensions::U64Extensions};
 
@@ -52,13 +53,13 @@ pub fn register_account(e: &Env, account: &[u8; 32]) {
             .unwrap();
 }
 
-pub fn register_stellar_asset_contract(e: &Env, admin: Address) -> Address {
+pub fn register_stellar_asset_contract(e: &Env, admin: Address, code: [u8; 4]) -> Address {
     let issuer_id = xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256(
         Constants::ADMIN.clone(),
     )));
 
     let asset = xdr::Asset::CreditAlphanum4(xdr::AlphaNum4 {
-        asset_code: xdr::AssetCode4([1, 1, 1, 1]),
+        asset_code: xdr::AssetCode4(code),
         issuer: issuer_id.clone(),
     });
     let create = xdr::HostFunction::CreateContract(xdr::CreateContractArgs {
@@ -89,6 +90,15 @@ fn mint(e: &Env, admin: &Address, token: &Address, user: &Address, amount: i128)
     token.mint(&admin, &user, &amount);
 }
 
+pub struct CallbackContract;
+
+#[contractimpl]
+impl CallbackContract {
+    pub fn on_price(e: Env, _asset: Address, price: PriceData) {
+        e.storage().set(&Symbol::short("price"), &price.price);
+    }
+}
+
 fn init_contract_with_admin() -> (Env, PriceOracleContractClient, ConfigData, Address) {
     let env = Env::default();
 
@@ -107,7 +117,7 @@ fn init_contract_with_admin() -> (Env, PriceOracleContractClient, ConfigData, Ad
         base_fee: 100,
     };
 
-    let token = register_stellar_asset_contract(&env, config_data.admin.clone());
+    let token = register_stellar_asset_contract(&env, config_data.admin.clone(), [1, 1, 1, 1]);
 
     let default_admin = Address::from_account_id(&env, &BytesN::from_array(&env, &Constants::ADMIN));
 
@@ -147,7 +157,7 @@ fn deposit(
     client: &PriceOracleContractClient,
     config_data: &ConfigData,
     user: &Address,
-    contract: &BytesN<32>,
+    contract: &Address,
     token: &Address,
     amount: &i128,
 ) {
@@ -161,13 +171,24 @@ fn deposit_random_contract(
     config_data: &ConfigData,
     token: &Address,
     amount: &i128,
-) -> BytesN<32> {
+) -> Address {
     let user = Address::random(&e);
-    let contract = Address::random(&e).contract_id().unwrap();
+    let contract = Address::random(&e);
     deposit(e, client, config_data, &user, &contract, token, amount);
     contract
 }
 
+fn generate_signer(secret: [u8; 32]) -> Keypair {
+    let secret = SecretKey::from_bytes(&secret).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+fn sign_price(env: &Env, signer: &Keypair, payload: &SignedPrice) -> BytesN<64> {
+    let message: alloc::vec::Vec<u8> = payload.clone().serialize(env).iter().collect();
+    BytesN::from_array(env, &signer.sign(&message).to_bytes())
+}
+
 #[test]
 fn init_test() {
     let (env, client, config_data, _) = init_contract_with_admin();
@@ -212,8 +233,8 @@ fn deposit_and_charge_test() {
     let timestamp = 600_000;
     client.set_price(&config_data.admin, &updates, &timestamp);
 
-    let price = env.as_contract(&contract, || {
-        client.lastprice(&config_data.assets.get_unchecked(1).unwrap())
+    let price = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &config_data.assets.get_unchecked(1).unwrap())
     });
     assert_ne!(price, None);
 
@@ -222,6 +243,947 @@ fn deposit_and_charge_test() {
     assert_eq!(balance, Some(0));
 }
 
+#[test]
+fn fee_schedule_test() {
+    let (env, client, config_data, _) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let args = FeeArgs {
+        assets: Vec::from_array(&env, [asset.clone()]),
+        records: 5,
+    };
+
+    //default multipliers
+    assert_eq!(client.quote_fee(&Endpoint::Prices, &args), 500);
+    assert_eq!(client.quote_fee(&Endpoint::XPrices, &args), 1000);
+
+    client.set_endpoint_fee(
+        &admin,
+        &Endpoint::Prices,
+        &EndpointFee {
+            multiplier: 2,
+            record_multiplier: 3,
+        },
+    );
+    client.set_asset_fee(&admin, &asset, &50);
+
+    assert_eq!(client.quote_fee(&Endpoint::Prices, &args), 1750);
+    assert_eq!(client.asset_fee(&asset), Some(50));
+
+    client.set_asset_fee(&admin, &asset, &0);
+    assert_eq!(client.asset_fee(&asset), None);
+}
+
+#[test]
+#[should_panic]
+fn caller_mismatch_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &100);
+
+    //another contract tries to bill the consumer
+    let invoker = Address::random(&env);
+    env.as_contract(&invoker.contract_id().unwrap(), || {
+        client.lastprice(&contract, &config_data.assets.get_unchecked(1).unwrap())
+    });
+}
+
+#[test]
+fn originator_billing_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    client.set_billing_mode(&config_data.admin, &BillingMode::Originator);
+
+    //the account pays for the read made through the consumer contract
+    let account = Address::random(&env);
+    deposit(&env, &client, &config_data, &account, &account, &token, &100);
+
+    let invoker = Address::random(&env);
+    let price = env.as_contract(&invoker.contract_id().unwrap(), || {
+        client.lastprice(&account, &config_data.assets.get_unchecked(1).unwrap())
+    });
+    assert_ne!(price, None);
+    assert_eq!(client.balance(&account), Some(0));
+}
+
+#[test]
+fn legacy_balance_migration_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let contract = Address::random(&env);
+    let contract_id = contract.contract_id().unwrap();
+    env.as_contract(&client.contract_id, || {
+        env.storage().set(&DataKey::Balance(contract_id.clone()), &100i128);
+    });
+
+    assert_eq!(client.balance(&contract), Some(100));
+
+    //the legacy balance is moved on the first update
+    deposit(&env, &client, &config_data, &Address::random(&env), &contract, &token, &50);
+    assert_eq!(client.balance(&contract), Some(150));
+
+    let has_legacy_balance = env.as_contract(&client.contract_id, || {
+        env.storage().has(&DataKey::Balance(contract_id.clone()))
+    });
+    assert!(!has_legacy_balance);
+}
+
+#[test]
+fn withdraw_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &300);
+
+    let recipient = Address::random(&env);
+    client.withdraw(&contract, &recipient, &200);
+
+    assert_eq!(client.balance(&contract), Some(100));
+
+    let token_client = token::Client::new(&env, &token.contract_id().unwrap());
+    assert_eq!(token_client.balance(&recipient), 200);
+}
+
+#[test]
+#[should_panic]
+fn withdraw_insufficient_balance_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &100);
+
+    client.withdraw(&contract, &Address::random(&env), &200);
+}
+
+#[test]
+fn claim_fees_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &300);
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &config_data.assets.get_unchecked(1).unwrap())
+    });
+
+    assert_eq!(
+        client.treasury(),
+        Treasury {
+            deposits: 200,
+            earned: 100,
+            claimed: 0,
+            rewards: 0,
+            stakes: 0,
+            credits: 0
+        }
+    );
+
+    let fee_manager = Address::random(&env);
+    client.set_fee_manager(&config_data.admin, &fee_manager);
+
+    let recipient = Address::random(&env);
+    client.claim_fees(&fee_manager, &recipient, &100);
+
+    assert_eq!(
+        client.treasury(),
+        Treasury {
+            deposits: 200,
+            earned: 0,
+            claimed: 100,
+            rewards: 0,
+            stakes: 0,
+            credits: 0
+        }
+    );
+
+    let token_client = token::Client::new(&env, &token.contract_id().unwrap());
+    assert_eq!(token_client.balance(&recipient), 100);
+}
+
+#[test]
+fn deposit_additional_fee_asset_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+
+    //both fee assets should be priced by the oracle
+    let additional_token = register_stellar_asset_contract(&env, admin.clone(), [2, 2, 2, 2]);
+    client.add_assets(&admin, &Vec::from_array(&env, [token.clone(), additional_token.clone()]));
+
+    let mut updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    updates.push_back(normalize_price(1));
+    updates.push_back(normalize_price(2));
+    client.set_price(&admin, &updates, &600_000);
+
+    client.set_fee_asset(
+        &admin,
+        &additional_token,
+        &FeeAssetConfig {
+            haircut: 1000,
+            max_price_age: 600,
+        },
+    );
+
+    //the amounts are rescaled with the token decimals
+    let set_metadata = |asset: &Address, symbol: &str, decimals: u32| {
+        let metadata = AssetMetadata {
+            symbol: Symbol::short(symbol),
+            name: Bytes::new(&env),
+            description: Bytes::new(&env),
+            decimals,
+        };
+        client.set_asset_metadata(admin, asset, &metadata);
+    };
+    set_metadata(&token, "FEE", 7);
+    set_metadata(&additional_token, "ADD", 7);
+
+    let user = Address::random(&env);
+    mint(&env, &admin, &additional_token, &user, 1000);
+    let contract = Address::random(&env);
+    client.deposit(&user, &contract, &additional_token, &100);
+
+    //100 tokens at price 2 minus 10% haircut
+    assert_eq!(client.credit_balance(&contract), 180);
+    assert_eq!(client.balance(&contract), None);
+    assert_eq!(client.treasury().credits, 180);
+
+    //the tokens backing the unspent credit can't be claimed
+    assert_eq!(client.reserve(&additional_token), 0);
+    assert!(client.try_claim_reserve(&admin, &additional_token, &admin, &1).is_err());
+
+    //the credit can't be withdrawn
+    assert!(client.try_withdraw(&contract, &user, &180).is_err());
+
+    //the charged credit releases the tokens backing it to the reserve
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &asset)
+    });
+    assert_eq!(client.credit_balance(&contract), 80);
+    assert_eq!(client.reserve(&additional_token), 55);
+    client.claim_reserve(&admin, &additional_token, &admin, &55);
+    assert_eq!(client.reserve(&additional_token), 0);
+}
+
+#[test]
+fn sponsor_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let sponsor = Address::random(&env);
+    mint(&env, &config_data.admin, &token, &sponsor, 1000);
+    client.deposit_sponsor(&sponsor, &sponsor, &token, &1000);
+
+    let consumer = Address::random(&env);
+    client.sponsor(&sponsor, &Vec::from_array(&env, [consumer.clone()]), &200);
+
+    //the consumer has no balance, so the fee is charged from the sponsor
+    for _ in 0..2 {
+        let price = env.as_contract(&consumer.contract_id().unwrap(), || {
+            client.lastprice(&consumer, &asset)
+        });
+        assert_ne!(price, None);
+    }
+    assert_eq!(client.sponsor_balance(&sponsor), Some(800));
+    assert_eq!(client.sponsorship(&consumer).unwrap().spent, 200);
+
+    client.revoke_sponsor(&sponsor, &Vec::from_array(&env, [consumer.clone()]));
+    assert_eq!(client.sponsorship(&consumer), None);
+
+    //the consumer can leave the sponsorship on its own
+    client.sponsor(&sponsor, &Vec::from_array(&env, [consumer.clone()]), &200);
+    client.leave_sponsorship(&consumer);
+    assert_eq!(client.sponsorship(&consumer), None);
+}
+
+#[test]
+#[should_panic]
+fn sponsor_cap_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let sponsor = Address::random(&env);
+    mint(&env, &config_data.admin, &token, &sponsor, 1000);
+    client.deposit_sponsor(&sponsor, &sponsor, &token, &1000);
+
+    let consumer = Address::random(&env);
+    client.sponsor(&sponsor, &Vec::from_array(&env, [consumer.clone()]), &100);
+
+    //the second read exceeds the cap
+    for _ in 0..2 {
+        env.as_contract(&consumer.contract_id().unwrap(), || client.lastprice(&consumer, &asset));
+    }
+}
+
+#[test]
+fn free_tier_and_discount_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&admin, &updates, &600_000);
+
+    client.set_free_tier(&admin, &FreeTier { calls: 2, period: 3600 });
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &100);
+    client.set_discount(&admin, &Vec::from_array(&env, [contract.clone()]), &5000);
+    assert_eq!(client.discount(&contract), Some(5000));
+
+    //the first two reads are free, the third one is discounted
+    for _ in 0..3 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+    assert_eq!(client.free_calls(&contract), 0);
+    assert_eq!(client.balance(&contract), Some(50));
+
+    //the quota resets in the next period
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(client.free_calls(&contract), 2);
+
+    //the fully discounted reads don't use the quota
+    client.set_discount(&admin, &Vec::from_array(&env, [contract.clone()]), &10000);
+    env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    assert_eq!(client.free_calls(&contract), 2);
+    assert_eq!(client.balance(&contract), Some(50));
+}
+
+#[test]
+#[should_panic]
+fn zero_usage_period_test() {
+    let (_, client, config_data, _) = init_contract_with_admin();
+
+    client.set_usage_period(&config_data.admin, &0);
+}
+
+#[test]
+fn usage_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+
+    for _ in 0..2 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+    env.as_contract(&contract.contract_id().unwrap(), || client.prices(&contract, &asset, &1));
+
+    let usage = client.usage(&contract, &0, &86399);
+    assert_eq!(usage.len(), 1);
+
+    let usage = usage.get_unchecked(0).unwrap();
+    assert_eq!(usage.period, 0);
+    assert_eq!(usage.fees, 300);
+    assert_eq!(usage.calls.get_unchecked(Endpoint::LastPrice).unwrap(), 2);
+    assert_eq!(usage.calls.get_unchecked(Endpoint::Prices).unwrap(), 1);
+}
+
+#[test]
+#[should_panic]
+fn zero_limits_period_test() {
+    let (env, client, _, _) = init_contract_with_admin();
+
+    client.set_limits(
+        &Address::random(&env),
+        &ConsumerLimits {
+            calls_per_ledger: 0,
+            calls_per_period: 10,
+            fees_per_period: 0,
+            period: 0,
+        },
+    );
+}
+
+#[test]
+#[should_panic]
+fn rate_limit_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+    client.set_limits(
+        &contract,
+        &ConsumerLimits {
+            calls_per_ledger: 2,
+            calls_per_period: 0,
+            fees_per_period: 0,
+            period: 3600,
+        },
+    );
+
+    //the third read in the same ledger exceeds the limit
+    for _ in 0..3 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+}
+
+#[test]
+fn top_up_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &100);
+
+    let funder = Address::random(&env);
+    mint(&env, &config_data.admin, &token, &funder, 1000);
+    let token_client = token::Client::new(&env, &token.contract_id().unwrap());
+    token_client.incr_allow(&funder, &Address::from_contract_id(&env, &client.contract_id), &1000);
+
+    client.set_low_balance(&contract, &150);
+    client.set_top_up(&contract, &funder, &300);
+
+    //the first read spends the deposit, the second one pulls the top-up
+    for _ in 0..2 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+
+    assert_eq!(client.balance(&contract), Some(200));
+    assert_eq!(token_client.balance(&funder), 700);
+}
+
+#[test]
+fn low_balance_event_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&config_data.admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &300);
+    client.set_low_balance(&contract, &250);
+
+    //the read crosses the threshold
+    env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    assert_eq!(client.balance(&contract), Some(200));
+
+    let events = env.events().all();
+    let (contract_id, topics, data) = events.get_unchecked(events.len() - 1).unwrap();
+    assert_eq!(contract_id, client.contract_id);
+    let topic: Symbol = topics.get_unchecked(0).unwrap().try_into_val(&env).unwrap();
+    assert_eq!(topic, Symbol::short("low_bal"));
+    let data: (i128, i128) = data.try_into_val(&env).unwrap();
+    assert_eq!(data, (200, 250));
+}
+
+#[test]
+fn provider_rewards_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let provider_a = Address::random(&env);
+    let provider_b = Address::random(&env);
+    client.add_providers(admin, &Vec::from_array(&env, [provider_a.clone(), provider_b.clone()]));
+    client.set_provider_share(admin, &5000);
+    client.set_quorum(admin, &1);
+    env.ledger().with_mut(|li| li.timestamp = 1_500);
+
+    //provider a contributes 1 round, provider b contributes 3 rounds
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&provider_a, &updates, &600_000);
+    for timestamp in [900_000, 1_200_000, 1_500_000] {
+        client.set_price(&provider_b, &updates, &timestamp);
+    }
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+    for _ in 0..2 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+
+    assert_eq!(client.rewards(&provider_a), 25);
+    assert_eq!(client.rewards(&provider_b), 75);
+
+    let recipient = Address::random(&env);
+    client.claim_rewards(&provider_b, &recipient, &75);
+
+    assert_eq!(client.rewards(&provider_b), 0);
+    assert_eq!(
+        client.treasury(),
+        Treasury {
+            deposits: 800,
+            earned: 100,
+            claimed: 75,
+            rewards: 25,
+            stakes: 0,
+            credits: 0
+        }
+    );
+
+    let token_client = token::Client::new(&env, &token.contract_id().unwrap());
+    assert_eq!(token_client.balance(&recipient), 75);
+}
+
+#[test]
+fn provider_resubmission_rewards_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let provider_a = Address::random(&env);
+    let provider_b = Address::random(&env);
+    client.add_providers(admin, &Vec::from_array(&env, [provider_a.clone(), provider_b.clone()]));
+    client.set_provider_share(admin, &5000);
+
+    env.ledger().with_mut(|li| li.timestamp = 600);
+
+    //both providers contribute the same round, provider b resubmits it
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&provider_a, &updates, &600_000);
+    for _ in 0..3 {
+        client.set_price(&provider_b, &updates, &600_000);
+    }
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+    for _ in 0..2 {
+        env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+    }
+
+    assert_eq!(client.rewards(&provider_a), 50);
+    assert_eq!(client.rewards(&provider_b), 50);
+}
+
+#[test]
+fn provider_quorum_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let providers = generate_assets(&env, 3);
+    client.add_providers(admin, &providers);
+    assert_eq!(client.quorum(), 2);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+    let get_last_price = || env.as_contract(&contract.contract_id().unwrap(), || client.lastprice(&contract, &asset));
+
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+
+    //a single provider can't set the round prices
+    client.set_price(&providers.get_unchecked(0).unwrap(), &updates, &600_000);
+    assert_eq!(get_last_price(), None);
+
+    //the unaligned timestamp is normalized to the round
+    client.set_price(&providers.get_unchecked(1).unwrap(), &updates, &600_123);
+    let price = get_last_price().unwrap();
+    assert_eq!(price.price, normalize_price(100));
+    assert_eq!(price.timestamp, 600_000);
+}
+
+#[test]
+#[should_panic]
+fn provider_future_timestamp_test() {
+    let (env, client, config_data, _) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let provider = Address::random(&env);
+    client.add_providers(admin, &Vec::from_array(&env, [provider.clone()]));
+
+    //the round ahead of the ledger time by more than the resolution is rejected
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&provider, &updates, &1_200_000);
+}
+
+#[test]
+fn provider_staking_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+
+    client.set_staking_config(
+        admin,
+        &StakingConfig {
+            min_stake: 100,
+            unbonding_period: 3600,
+            max_deviation: 500,
+            slash_share: 5000,
+        },
+    );
+
+    let providers = generate_assets(&env, 3);
+    client.add_providers(admin, &providers);
+    for provider in providers.iter() {
+        let provider = provider.unwrap();
+        mint(&env, admin, &token, &provider, 1000);
+        client.stake(&provider, &1000);
+    }
+
+    env.ledger().with_mut(|li| li.timestamp = 900);
+
+    //the third provider deviates from the median by 20%
+    let prices = [100, 100, 120];
+    for i in 0..3 {
+        let updates = get_updates(&env, &config_data.assets, normalize_price(prices[i]));
+        client.set_price(&providers.get_unchecked(i as u32).unwrap(), &updates, &600_000);
+    }
+
+    //the next round finalizes the previous one
+    let provider = providers.get_unchecked(0).unwrap();
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&provider, &updates, &900_000);
+
+    assert_eq!(client.provider_stake(&providers.get_unchecked(2).unwrap()).amount, 500);
+    assert_eq!(client.treasury().earned, 500);
+    assert_eq!(client.treasury().stakes, 2500);
+
+    client.unbond(&provider, &400);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+
+    let recipient = Address::random(&env);
+    client.withdraw_stake(&provider, &recipient);
+
+    assert_eq!(
+        client.provider_stake(&provider),
+        ProviderStake {
+            amount: 600,
+            unbonding: 0,
+            unbonding_until: 4500
+        }
+    );
+    let token_client = token::Client::new(&env, &token.contract_id().unwrap());
+    assert_eq!(token_client.balance(&recipient), 400);
+}
+
+#[test]
+fn submit_price_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let signer = generate_signer([3; 32]);
+    let signer_key = BytesN::from_array(&env, &signer.public.to_bytes());
+    client.add_signers(&config_data.admin, &Vec::from_array(&env, [signer_key.clone()]));
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+    let submit = |payload: &SignedPrice| {
+        let signature = sign_price(&env, &signer, payload);
+        env.as_contract(&contract.contract_id().unwrap(), || {
+            client.try_submit_price(&contract, payload, &signer_key, &signature)
+        })
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    let mut payload = SignedPrice {
+        asset: asset.clone(),
+        price: normalize_price(100),
+        timestamp: 900_000,
+        contract_id: client.contract_id.clone(),
+        nonce: 1,
+    };
+
+    //the valid payload is accepted
+    let price = submit(&payload).unwrap().unwrap();
+    assert_eq!(price.price, normalize_price(100));
+    assert_eq!(price.timestamp, 900_000);
+
+    //the used nonce can't be replayed
+    assert!(submit(&payload).is_err());
+
+    //the payload signed for another contract is rejected
+    payload.nonce = 2;
+    payload.contract_id = BytesN::from_array(&env, &[9; 32]);
+    assert!(submit(&payload).is_err());
+
+    //the stale payload does not overwrite the fresher price
+    payload.contract_id = client.contract_id.clone();
+    payload.price = normalize_price(90);
+    payload.timestamp = 600_000;
+    let price = submit(&payload).unwrap().unwrap();
+    assert_eq!(price.price, normalize_price(100));
+    assert_eq!(price.timestamp, 900_000);
+}
+
+#[test]
+#[should_panic]
+fn submit_price_unknown_signer_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+
+    let payload = SignedPrice {
+        asset: config_data.assets.get_unchecked(1).unwrap(),
+        price: normalize_price(100),
+        timestamp: 600_000,
+        contract_id: client.contract_id.clone(),
+        nonce: 1,
+    };
+
+    env.as_contract(&contract.contract_id().unwrap(), || {
+        client.submit_price(
+            &contract,
+            &payload,
+            &BytesN::from_array(&env, &[1; 32]),
+            &BytesN::from_array(&env, &[0; 64]),
+        )
+    });
+}
+
+#[test]
+fn commit_reveal_test() {
+    let (env, client, config_data, _) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+
+    client.set_commit_reveal(
+        admin,
+        &CommitRevealConfig {
+            commit_window: 60_000,
+            reveal_window: 60_000,
+        },
+    );
+
+    let providers = generate_assets(&env, 2);
+    client.add_providers(admin, &providers);
+    let provider_a = providers.get_unchecked(0).unwrap();
+    let provider_b = providers.get_unchecked(1).unwrap();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    let salt = BytesN::from_array(&env, &[7; 32]);
+    let mut data = updates.clone().serialize(&env);
+    data.append(&salt.clone().into());
+    let hash = env.crypto().sha256(&data);
+
+    //both providers commit, only the first one reveals
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    client.commit_price(&provider_a, &600_000, &hash);
+    client.commit_price(&provider_b, &600_000, &hash);
+
+    //the timestamp is normalized to the committed round
+    env.ledger().with_mut(|li| li.timestamp = 660);
+    client.reveal_price(&provider_a, &updates, &salt, &600_500);
+
+    //the next round finalizes the previous one
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    client.commit_price(&provider_a, &900_000, &hash);
+
+    assert_eq!(client.missed_reveals(&provider_a), 0);
+    assert_eq!(client.missed_reveals(&provider_b), 1);
+}
+
+#[test]
+fn callback_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let callback_id = env.register_contract(None, CallbackContract);
+    let consumer = Address::from_contract_id(&env, &callback_id);
+    deposit(&env, &client, &config_data, &Address::random(&env), &consumer, &token, &1000);
+
+    client.register_callback(&consumer, &asset, &Symbol::short("on_price"), &1000, &0);
+
+    let get_callback_price = || -> Option<i128> {
+        env.as_contract(&callback_id, || env.storage().get(&Symbol::short("price")).map(|price| price.unwrap()))
+    };
+
+    //the first update always triggers the callback
+    client.set_price(admin, &get_updates(&env, &config_data.assets, normalize_price(100)), &600_000);
+    assert_eq!(get_callback_price(), Some(normalize_price(100)));
+
+    //5% deviation is below the trigger
+    client.set_price(admin, &get_updates(&env, &config_data.assets, normalize_price(105)), &900_000);
+    assert_eq!(get_callback_price(), Some(normalize_price(100)));
+
+    client.set_price(admin, &get_updates(&env, &config_data.assets, normalize_price(120)), &1_200_000);
+    assert_eq!(get_callback_price(), Some(normalize_price(120)));
+
+    assert_eq!(client.balance(&consumer), Some(800));
+}
+
+#[test]
+#[should_panic]
+fn callback_invalid_deviation_test() {
+    let (env, client, config_data, _) = init_contract_with_admin();
+
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+    let consumer = Address::from_contract_id(&env, &env.register_contract(None, CallbackContract));
+
+    client.register_callback(&consumer, &asset, &Symbol::short("on_price"), &10001, &0);
+}
+
+#[test]
+fn callback_sponsor_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let callback_id = env.register_contract(None, CallbackContract);
+    let consumer = Address::from_contract_id(&env, &callback_id);
+    client.register_callback(&consumer, &asset, &Symbol::short("on_price"), &1000, &0);
+
+    //the consumer has no balance, so the callback is charged from the sponsor
+    let sponsor = Address::random(&env);
+    mint(&env, admin, &token, &sponsor, 1000);
+    client.deposit_sponsor(&sponsor, &sponsor, &token, &1000);
+    client.sponsor(&sponsor, &Vec::from_array(&env, [consumer.clone()]), &0);
+
+    client.set_price(admin, &get_updates(&env, &config_data.assets, normalize_price(100)), &600_000);
+
+    let price: Option<i128> =
+        env.as_contract(&callback_id, || env.storage().get(&Symbol::short("price")).map(|price| price.unwrap()));
+    assert_eq!(price, Some(normalize_price(100)));
+    assert_eq!(client.sponsor_balance(&sponsor), Some(900));
+}
+
+#[test]
+fn callback_round_limit_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let provider_a = Address::random(&env);
+    let provider_b = Address::random(&env);
+    client.add_providers(admin, &Vec::from_array(&env, [provider_a.clone(), provider_b.clone()]));
+
+    let mut callback_ids = Vec::new(&env);
+    for _ in 0..12 {
+        let callback_id = env.register_contract(None, CallbackContract);
+        let consumer = Address::from_contract_id(&env, &callback_id);
+        deposit(&env, &client, &config_data, &Address::random(&env), &consumer, &token, &1000);
+        client.register_callback(&consumer, &asset, &Symbol::short("on_price"), &1000, &0);
+        callback_ids.push_back(callback_id);
+    }
+
+    let count_invoked = || -> u32 {
+        let mut invoked = 0;
+        for callback_id in callback_ids.iter() {
+            let callback_id = callback_id.unwrap();
+            let price: Option<i128> =
+                env.as_contract(&callback_id, || env.storage().get(&Symbol::short("price")).map(|price| price.unwrap()));
+            if price.is_some() {
+                invoked += 1;
+            }
+        }
+        invoked
+    };
+
+    //every submission of the same round shares the limit
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&provider_a, &updates, &600_000);
+    client.set_price(&provider_b, &updates, &600_000);
+    assert_eq!(count_invoked(), 10);
+
+    //the rest is triggered by the next round
+    client.set_price(&provider_a, &updates, &900_000);
+    client.set_price(&provider_b, &updates, &900_000);
+    assert_eq!(count_invoked(), 12);
+}
+
+#[test]
+#[should_panic]
+fn negative_plan_price_test() {
+    let (env, client, config_data, _) = init_contract_with_admin();
+
+    let plan = SubscriptionPlan {
+        price: -300,
+        duration: 3600,
+        max_calls: 0,
+        assets: Vec::new(&env),
+    };
+    client.set_plan(&config_data.admin, &1, &plan);
+}
+
+#[test]
+fn subscription_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&admin, &updates, &600_000);
+
+    let plan = SubscriptionPlan {
+        price: 300,
+        duration: 3600,
+        max_calls: 0,
+        assets: Vec::new(&env),
+    };
+    client.set_plan(&admin, &1, &plan);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &400);
+
+    client.subscribe(&contract, &1);
+    assert_eq!(client.balance(&contract), Some(100));
+
+    //reads inside the subscription are not charged
+    for _ in 0..3 {
+        let price = env.as_contract(&contract.contract_id().unwrap(), || {
+            client.lastprice(&contract, &asset)
+        });
+        assert_ne!(price, None);
+    }
+    assert_eq!(client.balance(&contract), Some(100));
+    assert_eq!(client.subscription(&contract).unwrap().calls, 3);
+
+    //expire the subscription
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(client.subscription(&contract), None);
+
+    let price = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &asset)
+    });
+    assert_ne!(price, None);
+    assert_eq!(client.balance(&contract), Some(0));
+}
+
+#[test]
+fn no_charge_for_missing_data_test() {
+    let (env, client, config_data, token) = init_contract_with_admin();
+
+    let admin = &config_data.admin;
+    let asset = config_data.assets.get_unchecked(1).unwrap();
+
+    let updates = get_updates(&env, &config_data.assets, normalize_price(100));
+    client.set_price(&admin, &updates, &600_000);
+
+    let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
+
+    //unknown asset
+    let price = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &Address::random(&env))
+    });
+    assert_eq!(price, None);
+    assert_eq!(client.balance(&contract), Some(1000));
+
+    //only one of the requested records is available
+    let prices = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.prices(&contract, &asset, &5)
+    });
+    assert_eq!(prices.unwrap().len(), 1);
+    assert_eq!(client.balance(&contract), Some(900));
+}
+
 #[test]
 fn last_price_test() {
     let (env, client, config_data, token) = init_contract_with_admin();
@@ -244,15 +1206,16 @@ fn last_price_test() {
     //check last prices
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &100);
 
-    let result = env.as_contract(&contract, || {
-        client.lastprice(&assets.get_unchecked(1).unwrap())
+    let result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &assets.get_unchecked(1).unwrap())
     });
     assert_ne!(result, None);
     assert_eq!(
         result,
         Some(PriceData {
             price: normalize_price(200),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -277,28 +1240,30 @@ fn get_price_test() {
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &200);
 
     //check last prices
-    let mut result = env.as_contract(&contract, || {
-        client.lastprice(&assets.get_unchecked(1).unwrap())
+    let mut result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &assets.get_unchecked(1).unwrap())
     });
     assert_ne!(result, None);
     assert_eq!(
         result,
         Some(PriceData {
             price: normalize_price(200),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 
     //check price at 899_000
-    result = env.as_contract(&contract, || {
-        client.price(&assets.get_unchecked(1).unwrap(), &899_000)
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.price(&contract, &assets.get_unchecked(1).unwrap(), &899_000)
     });
     assert_ne!(result, None);
     assert_eq!(
         result,
         Some(PriceData {
             price: normalize_price(100),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -318,8 +1283,9 @@ fn get_x_last_price_test() {
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &200);
 
     //check last x price
-    let result = env.as_contract(&contract, || {
+    let result = env.as_contract(&contract.contract_id().unwrap(), || {
         client.x_last_price(
+            &contract,
             &assets.get_unchecked(1).unwrap(),
             &assets.get_unchecked(2).unwrap(),
         )
@@ -329,7 +1295,8 @@ fn get_x_last_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -356,8 +1323,9 @@ fn get_x_price_test() {
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &400);
 
     //check last prices
-    let mut result = env.as_contract(&contract, || {
+    let mut result = env.as_contract(&contract.contract_id().unwrap(), || {
         client.x_last_price(
+            &contract,
             &assets.get_unchecked(1).unwrap(),
             &assets.get_unchecked(2).unwrap(),
         )
@@ -367,13 +1335,15 @@ fn get_x_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 
     //check price at 899_000
-    result = env.as_contract(&contract, || {
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
         client.x_price(
+            &contract,
             &assets.get_unchecked(1).unwrap(),
             &assets.get_unchecked(2).unwrap(),
             &899_000,
@@ -384,7 +1354,8 @@ fn get_x_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -410,8 +1381,8 @@ fn twap_test() {
 
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &200);
 
-    let result = env.as_contract(&contract, || {
-        client.twap(&assets.get_unchecked(1).unwrap(), &2)
+    let result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.twap(&contract, &assets.get_unchecked(1).unwrap(), &2)
     });
 
     assert_ne!(result, None);
@@ -439,8 +1410,9 @@ fn x_twap_test() {
 
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &400);
 
-    let result = env.as_contract(&contract, || {
+    let result = env.as_contract(&contract.contract_id().unwrap(), || {
         client.x_twap(
+            &contract,
             &assets.get_unchecked(1).unwrap(),
             &assets.get_unchecked(2).unwrap(),
             &2,
@@ -458,19 +1430,27 @@ fn get_non_registered_asset_price_test() {
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &1000);
 
     //try to get price for unknown asset
-    let mut result = env.as_contract(&contract, || {client.lastprice(&Address::random(&env)) });
+    let mut result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &Address::random(&env))
+    });
     assert_eq!(result, None);
 
     //try to get price for unknown base asset
-    result = env.as_contract(&contract, || {client.x_last_price(&Address::random(&env), &config_data.assets.get_unchecked(1).unwrap()) });
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.x_last_price(&contract, &Address::random(&env), &config_data.assets.get_unchecked(1).unwrap())
+    });
     assert_eq!(result, None);
 
     //try to get price for unknown quote asset
-    result = env.as_contract(&contract, || {client.x_last_price(&config_data.assets.get_unchecked(1).unwrap(), &Address::random(&env)) });
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.x_last_price(&contract, &config_data.assets.get_unchecked(1).unwrap(), &Address::random(&env))
+    });
     assert_eq!(result, None);
 
     //try to get price for both unknown assets
-    result = env.as_contract(&contract, || {client.x_last_price(&Address::random(&env), &Address::random(&env)) });
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.x_last_price(&contract, &Address::random(&env), &Address::random(&env))
+    });
     assert_eq!(result, None);
 }
 
@@ -481,11 +1461,15 @@ fn get_asset_price_for_invalid_timestamp_test() {
     let contract = deposit_random_contract(&env, &client, &config_data, &token, &400);
 
     
-    let mut result = env.as_contract(&contract, || {client.price(&config_data.assets.get_unchecked(1).unwrap(), &u64::MAX) });
+    let mut result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.price(&contract, &config_data.assets.get_unchecked(1).unwrap(), &u64::MAX)
+    });
     assert_eq!(result, None);
 
     //try to get price for unknown asset
-    result = env.as_contract(&contract, || {client.lastprice(&Address::random(&env)) });
+    result = env.as_contract(&contract.contract_id().unwrap(), || {
+        client.lastprice(&contract, &Address::random(&env))
+    });
     assert_eq!(result, None);
 }
 
@@ -3,8 +3,8 @@
 mod test;
 
 use shared::price_oracle::PriceOracle;
-use shared::types::{config_data::ConfigData, price_data::PriceData};
-use soroban_sdk::{contractimpl, Address, Env, Vec};
+use shared::types::{asset_metadata::AssetMetadata, asset_schedule::AssetSchedule, config_data::ConfigData, error::Error, price_data::PriceData, round_data::RoundData, rounding::Rounding};
+use soroban_sdk::{contractimpl, Address, Env, Symbol, Vec};
 pub struct PriceOracleContract;
 
 #[contractimpl]
@@ -39,6 +39,81 @@ impl PriceOracleContract {
         PriceOracle::add_assets(&e, user, assets)
     }
 
+    /// Sets the metadata of the given asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `metadata` - The asset metadata.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, or if the symbol is used by another asset.
+    pub fn set_asset_metadata(e: Env, user: Address, asset: Address, metadata: AssetMetadata) {
+        PriceOracle::set_asset_metadata(&e, user, asset, metadata)
+    }
+
+    /// Sets the update schedule of the given asset. A zero heartbeat updates the asset every round. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `schedule` - The heartbeat and the deviation trigger.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, or if the heartbeat or the deviation is invalid.
+    pub fn set_asset_schedule(e: Env, user: Address, asset: Address, schedule: AssetSchedule) {
+        PriceOracle::set_asset_schedule(&e, user, asset, schedule)
+    }
+
+    /// Sets the price decimals of the given asset. Must be set before the first price update of the asset. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `asset` - The asset address.
+    /// * `decimals` - The number of decimals of the asset prices.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the asset is not supported, if the decimals exceed 24, or if the asset already has prices.
+    pub fn set_asset_decimals(e: Env, user: Address, asset: Address, decimals: u32) {
+        PriceOracle::set_asset_decimals(&e, user, asset, decimals)
+    }
+
+    /// Adds the given quote assets with direct price feeds. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `quotes` - The quote assets to add.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, or if the quotes are already added.
+    pub fn add_quotes(e: Env, user: Address, quotes: Vec<Address>) {
+        PriceOracle::add_quotes(&e, user, quotes)
+    }
+
+    /// Sets the prices for the assets in the given quote asset. The cross prices use them instead of the derived ones. Can only be called by the admin.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `user` - The admin address.
+    /// * `quote` - The quote asset address.
+    /// * `updates` - The prices to set in the contract decimals. 0 means no price for the asset.
+    /// * `timestamp` - The timestamp of the prices. It is normalized to the resolution.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the caller is not the admin, if the quote is not added, or if the prices are invalid.
+    pub fn set_quote_price(e: Env, user: Address, quote: Address, updates: Vec<i128>, timestamp: u64) {
+        PriceOracle::set_quote_price(&e, user, quote, updates, timestamp)
+    }
+
     /// Sets the prices for the assets. Can only be called by the admin.
     /// 
     /// # Arguments
@@ -49,7 +124,7 @@ impl PriceOracleContract {
     /// 
     /// # Panics
     /// 
-    /// Panics if the caller is not the admin, or if the prices are invalid.
+    /// Panics if the caller is not the admin, if the timestamp is older than the latest round, or if the prices are invalid.
     pub fn set_price(e: Env, user: Address, updates: Vec<i128>, timestamp: u64) {
         PriceOracle::set_price(&e, user, updates, timestamp)
     }
@@ -74,7 +149,7 @@ impl PriceOracleContract {
         PriceOracle::base(&e)
     }
 
-    /// Returns the number of decimals for the prices.
+    /// Returns the number of decimals for the cross prices. It is also the default number of decimals for the asset prices.
     /// 
     /// # Returns
     /// 
@@ -83,6 +158,19 @@ impl PriceOracleContract {
         PriceOracle::decimals(&e)
     }
 
+    /// Returns the number of decimals for the prices of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The number of decimals. Defaults to the contract decimals.
+    pub fn asset_decimals(e: Env, asset: Address) -> u32 {
+        PriceOracle::asset_decimals(&e, asset)
+    }
+
     /// Returns the prices resolution.
     /// 
     /// # Returns
@@ -110,6 +198,54 @@ impl PriceOracleContract {
         PriceOracle::assets(&e)
     }
 
+    /// Returns the quote assets with direct price feeds.
+    /// 
+    /// # Returns
+    /// 
+    /// The quote assets.
+    pub fn quotes(e: Env) -> Vec<Address> {
+        PriceOracle::quotes(&e)
+    }
+
+    /// Returns the metadata of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset metadata or None if the metadata is not set.
+    pub fn asset_metadata(e: Env, asset: Address) -> Option<AssetMetadata> {
+        PriceOracle::asset_metadata(&e, asset)
+    }
+
+    /// Returns the update schedule of the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset schedule or None if the asset is updated every round.
+    pub fn asset_schedule(e: Env, asset: Address) -> Option<AssetSchedule> {
+        PriceOracle::asset_schedule(&e, asset)
+    }
+
+    /// Returns the asset with the given ticker symbol.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `symbol` - The ticker symbol.
+    /// 
+    /// # Returns
+    /// 
+    /// The asset address or None if no asset has the symbol.
+    pub fn asset_by_symbol(e: Env, symbol: Symbol) -> Option<Address> {
+        PriceOracle::asset_by_symbol(&e, symbol)
+    }
+
     /// Returns the prices for the given asset at the given timestamp.
     /// 
     /// # Arguments
@@ -137,6 +273,33 @@ impl PriceOracleContract {
         PriceOracle::lastprice(&e, asset)
     }
 
+    /// Returns the data of the latest round for the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The latest round data for the given asset or None if the asset has no price in the latest round.
+    pub fn latest_round_data(e: Env, asset: Address) -> Option<RoundData> {
+        PriceOracle::latest_round_data(&e, asset)
+    }
+
+    /// Returns the data of the given round for the given asset.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// * `round_id` - The round id.
+    /// 
+    /// # Returns
+    /// 
+    /// The round data for the given asset or None if the round is unknown or expired, or if the asset has no price in the round.
+    pub fn round_data(e: Env, asset: Address, round_id: u64) -> Option<RoundData> {
+        PriceOracle::round_data(&e, asset, round_id)
+    }
+
     /// Returns the cross price for the given assets at the given timestamp.
     /// 
     /// # Arguments
@@ -231,4 +394,187 @@ impl PriceOracleContract {
     pub fn x_twap(e: Env, base_asset: Address, quote_asset: Address, records: u32) -> Option<i128> {
         PriceOracle::x_twap(&e, base_asset, quote_asset, records)
     }
+
+    /// Converts the amount of the from asset to the equivalent amount of the to asset at the last prices.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `from_asset` - The asset address to convert from.
+    /// * `to_asset` - The asset address to convert to.
+    /// * `amount` - The amount in the from asset token decimals.
+    /// * `rounding` - The rounding direction.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the converted amount overflows.
+    /// 
+    /// # Returns
+    /// 
+    /// The amount in the to asset token decimals or None if there are no prices or metadata for the assets.
+    pub fn convert(e: Env, from_asset: Address, to_asset: Address, amount: i128, rounding: Rounding) -> Option<i128> {
+        PriceOracle::convert(&e, from_asset, to_asset, amount, rounding)
+    }
+
+    /// Converts the amount of the from asset to the equivalent amount of the to asset at the prices of the given timestamp.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `from_asset` - The asset address to convert from.
+    /// * `to_asset` - The asset address to convert to.
+    /// * `amount` - The amount in the from asset token decimals.
+    /// * `rounding` - The rounding direction.
+    /// * `timestamp` - The timestamp of the prices.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the converted amount overflows.
+    /// 
+    /// # Returns
+    /// 
+    /// The amount in the to asset token decimals or None if there are no prices or metadata for the assets.
+    pub fn convert_at(
+        e: Env,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        rounding: Rounding,
+        timestamp: u64,
+    ) -> Option<i128> {
+        PriceOracle::convert_at(&e, from_asset, to_asset, amount, rounding, timestamp)
+    }
+
+    //checked reads, the try_ prefix is taken by the generated client
+
+    /// Returns the price for the given asset at the given timestamp. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// * `timestamp` - The timestamp.
+    /// 
+    /// # Returns
+    /// 
+    /// The price for the given asset at the given timestamp, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_price(e: Env, asset: Address, timestamp: u64) -> Result<PriceData, Error> {
+        PriceOracle::try_price(&e, asset, timestamp)
+    }
+
+    /// Returns the last price for the given asset. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The last price for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_lastprice(e: Env, asset: Address) -> Result<PriceData, Error> {
+        PriceOracle::try_lastprice(&e, asset)
+    }
+
+    /// Returns the cross price for the given assets at the given timestamp. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `timestamp` - The timestamp.
+    /// 
+    /// # Returns
+    /// 
+    /// The cross price for the given assets at the given timestamp, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_price(
+        e: Env,
+        base_asset: Address,
+        quote_asset: Address,
+        timestamp: u64,
+    ) -> Result<PriceData, Error> {
+        PriceOracle::try_x_price(&e, base_asset, quote_asset, timestamp)
+    }
+
+    /// Returns the last cross price for the given assets. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// 
+    /// # Returns
+    /// 
+    /// The last cross price for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_last_price(
+        e: Env,
+        base_asset: Address,
+        quote_asset: Address,
+    ) -> Result<PriceData, Error> {
+        PriceOracle::try_x_last_price(&e, base_asset, quote_asset)
+    }
+
+    /// Returns the last N prices for the given asset. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// * `records` - The number of records to return.
+    /// 
+    /// # Returns
+    /// 
+    /// The prices for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_prices(e: Env, asset: Address, records: u32) -> Result<Vec<PriceData>, Error> {
+        PriceOracle::try_prices(&e, asset, records)
+    }
+
+    /// Returns the last N cross prices for the given assets. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `records` - The number of records to return.
+    /// 
+    /// # Returns
+    /// 
+    /// The cross prices for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_prices(
+        e: Env,
+        base_asset: Address,
+        quote_asset: Address,
+        records: u32,
+    ) -> Result<Vec<PriceData>, Error> {
+        PriceOracle::try_x_prices(&e, base_asset, quote_asset, records)
+    }
+
+    /// Returns the time-weighted average price for the given asset over the given number of records. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `asset` - The asset address.
+    /// * `records` - The number of records to use.
+    /// 
+    /// # Returns
+    /// 
+    /// The time-weighted average price for the given asset, or Error::NotInitialized if there are no prices yet, Error::AssetNotFound if the asset is not supported, Error::StalePrice if the scheduled asset missed its heartbeat, or Error::NoDataForTimestamp otherwise.
+    pub fn checked_twap(e: Env, asset: Address, records: u32) -> Result<i128, Error> {
+        PriceOracle::try_twap(&e, asset, records)
+    }
+
+    /// Returns the time-weighted average cross price for the given assets over the given number of records. Returns the error instead of None.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `base_asset` - The base asset address.
+    /// * `quote_asset` - The quote asset address.
+    /// * `records` - The number of records to use.
+    /// 
+    /// # Returns
+    /// 
+    /// The time-weighted average cross price for the given assets, or the errors of the base or the quote asset price, or Error::DivisionByZero if the quote price is zero.
+    pub fn checked_x_twap(
+        e: Env,
+        base_asset: Address,
+        quote_asset: Address,
+        records: u32,
+    ) -> Result<i128, Error> {
+        PriceOracle::try_x_twap(&e, base_asset, quote_asset, records)
+    }
 }
\ No newline at end of file
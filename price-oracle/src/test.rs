@@ -4,7 +4,7 @@ extern crate alloc;
 
 use super::*;
 use alloc::rc::Rc;
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, xdr};
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Bytes, BytesN, Env, Symbol, xdr};
 
 use shared::{constants::Constants, extensions::u64_extensions::U64Extensions};
 
@@ -150,7 +150,8 @@ fn last_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(200),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -179,7 +180,8 @@ fn get_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(200),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 
@@ -190,7 +192,8 @@ fn get_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(100),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -217,7 +220,8 @@ fn get_x_last_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -251,7 +255,8 @@ fn get_x_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 900_000 as u64
+            timestamp: 900_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 
@@ -266,7 +271,8 @@ fn get_x_price_test() {
         result,
         Some(PriceData {
             price: normalize_price(1),
-            timestamp: 600_000 as u64
+            timestamp: 600_000 as u64,
+            decimals: Constants::DECIMALS
         })
     );
 }
@@ -372,4 +378,403 @@ fn unauthorized_test() {
     let timestamp = (112331 as u64).get_normalized_timestamp(Constants::RESOLUTION as u64);
     //set prices for assets
     client.set_price(&account, &updates, &timestamp);
-}
\ No newline at end of file
+}
+
+#[test]
+fn asset_metadata_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let asset = init_data.assets.get_unchecked(1).unwrap();
+
+    let metadata = AssetMetadata {
+        symbol: Symbol::short("XLM"),
+        name: Bytes::from_slice(&env, b"Stellar Lumens"),
+        description: Bytes::from_slice(&env, b"Stellar DEX VWAP"),
+        decimals: 7,
+    };
+    client.set_asset_metadata(&init_data.admin, &asset, &metadata);
+
+    assert_eq!(client.asset_metadata(&asset), Some(metadata.clone()));
+    assert_eq!(client.asset_by_symbol(&Symbol::short("XLM")), Some(asset.clone()));
+
+    //the previous symbol is released on update
+    let metadata = AssetMetadata {
+        symbol: Symbol::short("XLM2"),
+        ..metadata
+    };
+    client.set_asset_metadata(&init_data.admin, &asset, &metadata);
+
+    assert_eq!(client.asset_by_symbol(&Symbol::short("XLM")), None);
+    assert_eq!(client.asset_by_symbol(&Symbol::short("XLM2")), Some(asset));
+}
+
+#[test]
+#[should_panic]
+fn asset_metadata_duplicate_symbol_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let metadata = AssetMetadata {
+        symbol: Symbol::short("XLM"),
+        name: Bytes::from_slice(&env, b"Stellar Lumens"),
+        description: Bytes::new(&env),
+        decimals: 7,
+    };
+    client.set_asset_metadata(&init_data.admin, &init_data.assets.get_unchecked(1).unwrap(), &metadata);
+    client.set_asset_metadata(&init_data.admin, &init_data.assets.get_unchecked(2).unwrap(), &metadata);
+}
+
+#[test]
+fn asset_schedule_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let asset = init_data.assets.get_unchecked(1).unwrap();
+
+    client.set_asset_schedule(
+        admin,
+        &asset,
+        &AssetSchedule {
+            heartbeat: 900_000,
+            deviation: 1000,
+        },
+    );
+
+    let set_price = |price: i128, timestamp: u64| {
+        let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(100));
+        updates.set(1, price);
+        client.set_price(admin, &updates, &timestamp);
+    };
+
+    set_price(normalize_price(100), 600_000);
+    //the deviation is below the trigger and the heartbeat is not reached
+    set_price(normalize_price(101), 900_000);
+    //the deviation triggers the update
+    set_price(normalize_price(120), 1_200_000);
+    set_price(normalize_price(121), 1_500_000);
+
+    //the timestamp of the update in effect is reported
+    assert_eq!(
+        client.lastprice(&asset),
+        Some(PriceData {
+            price: normalize_price(120),
+            timestamp: 1_200_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+    assert_eq!(
+        client.price(&asset, &900_000),
+        Some(PriceData {
+            price: normalize_price(100),
+            timestamp: 600_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+
+    //each update is listed once
+    let prices = client.prices(&asset, &3).unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices.get_unchecked(0).unwrap().timestamp, 1_200_000);
+    assert_eq!(prices.get_unchecked(1).unwrap().timestamp, 600_000);
+
+    //the asset is not updated within the heartbeat
+    set_price(0, 1_800_000);
+    set_price(0, 2_100_000);
+    set_price(0, 2_400_000);
+    assert_eq!(client.lastprice(&asset), None);
+}
+
+#[test]
+fn asset_decimals_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let base_asset = init_data.assets.get_unchecked(1).unwrap();
+    let quote_asset = init_data.assets.get_unchecked(2).unwrap();
+
+    client.set_asset_decimals(&init_data.admin, &quote_asset, &18);
+    assert_eq!(client.asset_decimals(&quote_asset), 18);
+    assert_eq!(client.asset_decimals(&base_asset), Constants::DECIMALS);
+
+    let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(100));
+    updates.set(2, 50 * 10i128.pow(18));
+    client.set_price(&init_data.admin, &updates, &600_000);
+
+    assert_eq!(
+        client.lastprice(&quote_asset),
+        Some(PriceData {
+            price: 50 * 10i128.pow(18),
+            timestamp: 600_000,
+            decimals: 18
+        })
+    );
+
+    //the cross price is rescaled to the contract decimals
+    assert_eq!(
+        client.x_last_price(&base_asset, &quote_asset),
+        Some(PriceData {
+            price: normalize_price(2),
+            timestamp: 600_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+}
+
+#[test]
+fn x_price_decimals_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let asset_14 = init_data.assets.get_unchecked(1).unwrap();
+    let asset_6 = init_data.assets.get_unchecked(2).unwrap();
+    let asset_18 = init_data.assets.get_unchecked(3).unwrap();
+
+    client.set_asset_decimals(admin, &asset_6, &6);
+    client.set_asset_decimals(admin, &asset_18, &18);
+
+    let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(100));
+    updates.set(2, 3 * 10i128.pow(6));
+    updates.set(3, 7 * 10i128.pow(18));
+    client.set_price(admin, &updates, &600_000);
+
+    let get_x_price = |base: &Address, quote: &Address| client.x_last_price(base, quote).unwrap().price;
+
+    //the cross prices keep the full precision of the contract decimals
+    assert_eq!(get_x_price(&asset_6, &asset_18), 42_857_142_857_142);
+    assert_eq!(get_x_price(&asset_18, &asset_6), 233_333_333_333_333);
+    assert_eq!(get_x_price(&asset_14, &asset_6), 3_333_333_333_333_333);
+    assert_eq!(get_x_price(&asset_6, &asset_14), 3_000_000_000_000);
+    assert_eq!(get_x_price(&asset_18, &asset_14), 7_000_000_000_000);
+}
+
+#[test]
+fn quote_price_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let base_asset = init_data.assets.get_unchecked(1).unwrap();
+    let quote_asset = init_data.assets.get_unchecked(2).unwrap();
+
+    let quote = Address::random(&env);
+    client.add_quotes(admin, &Vec::from_array(&env, [quote.clone()]));
+
+    client.set_price(admin, &get_updates(&env, init_data.assets.clone(), normalize_price(100)), &600_000);
+    client.set_quote_price(admin, &quote, &get_updates(&env, init_data.assets.clone(), normalize_price(3)), &600_000);
+
+    //the direct feed is used for the quote
+    assert_eq!(
+        client.x_last_price(&base_asset, &quote),
+        Some(PriceData {
+            price: normalize_price(3),
+            timestamp: 600_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+
+    //the cross price is derived if there is no direct feed
+    assert_eq!(
+        client.x_last_price(&base_asset, &quote_asset),
+        Some(PriceData {
+            price: normalize_price(1),
+            timestamp: 600_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+
+    //the timestamp is normalized to the round, and the round becomes the last one
+    client.set_quote_price(admin, &quote, &get_updates(&env, init_data.assets.clone(), normalize_price(4)), &900_123);
+    assert_eq!(
+        client.x_last_price(&base_asset, &quote),
+        Some(PriceData {
+            price: normalize_price(4),
+            timestamp: 900_000,
+            decimals: Constants::DECIMALS
+        })
+    );
+}
+
+#[test]
+#[should_panic]
+fn backfill_round_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let updates = get_updates(&env, init_data.assets.clone(), normalize_price(100));
+    client.set_price(admin, &updates, &900_000);
+
+    //the older slot would get a higher round id
+    client.set_price(admin, &updates, &600_000);
+}
+
+#[test]
+fn convert_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let from_asset = init_data.assets.get_unchecked(1).unwrap();
+    let to_asset = init_data.assets.get_unchecked(2).unwrap();
+
+    let set_metadata = |asset: &Address, symbol: &str, decimals: u32| {
+        let metadata = AssetMetadata {
+            symbol: Symbol::short(symbol),
+            name: Bytes::new(&env),
+            description: Bytes::new(&env),
+            decimals,
+        };
+        client.set_asset_metadata(admin, asset, &metadata);
+    };
+    set_metadata(&from_asset, "FROM", 7);
+    set_metadata(&to_asset, "TO", 18);
+
+    let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(2));
+    updates.set(2, normalize_price(3));
+    client.set_price(admin, &updates, &600_000);
+
+    //1 from token costs 2/3 of the to token
+    let amount = 10i128.pow(7);
+    assert_eq!(
+        client.convert(&from_asset, &to_asset, &amount, &Rounding::Floor),
+        Some(666_666_666_666_666_666)
+    );
+    assert_eq!(
+        client.convert_at(&from_asset, &to_asset, &amount, &Rounding::Ceil, &600_000),
+        Some(666_666_666_666_666_667)
+    );
+
+    //there is no metadata for the asset
+    assert_eq!(
+        client.convert(&from_asset, &init_data.assets.get_unchecked(3).unwrap(), &amount, &Rounding::Floor),
+        None
+    );
+}
+
+#[test]
+fn convert_cross_price_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let from_asset = init_data.assets.get_unchecked(3).unwrap();
+    let to_asset = init_data.assets.get_unchecked(4).unwrap();
+
+    let set_metadata = |asset: &Address, symbol: &str| {
+        let metadata = AssetMetadata {
+            symbol: Symbol::short(symbol),
+            name: Bytes::new(&env),
+            description: Bytes::new(&env),
+            decimals: 7,
+        };
+        client.set_asset_metadata(admin, asset, &metadata);
+    };
+    set_metadata(&from_asset, "FROM");
+    set_metadata(&to_asset, "TO");
+
+    //the prices of the pair have different decimals
+    client.set_asset_decimals(admin, &from_asset, &6);
+    client.set_asset_decimals(admin, &to_asset, &18);
+
+    let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(1));
+    updates.set(3, 10i128.pow(6));
+    updates.set(4, 3 * 10i128.pow(18));
+    client.set_price(admin, &updates, &600_000);
+
+    //1 from token costs 1/3 of the to token, the rounding applies to the converted amount
+    let amount = 10i128.pow(20);
+    assert_eq!(
+        client.convert(&from_asset, &to_asset, &amount, &Rounding::Floor),
+        Some(33_333_333_333_333_333_333)
+    );
+    assert_eq!(
+        client.convert(&from_asset, &to_asset, &amount, &Rounding::Ceil),
+        Some(33_333_333_333_333_333_334)
+    );
+    assert_eq!(
+        client.convert(&to_asset, &from_asset, &10i128.pow(7), &Rounding::Floor),
+        Some(3 * 10i128.pow(7))
+    );
+}
+
+#[test]
+fn round_data_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let admin = &init_data.admin;
+    let asset = init_data.assets.get_unchecked(1).unwrap();
+    let carried_asset = init_data.assets.get_unchecked(2).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp = 600);
+    client.set_price(admin, &get_updates(&env, init_data.assets.clone(), normalize_price(100)), &600_000);
+
+    //the second asset is carried over from the previous round
+    env.ledger().with_mut(|li| li.timestamp = 900);
+    let mut updates = get_updates(&env, init_data.assets.clone(), normalize_price(105));
+    updates.set(2, 0);
+    client.set_price(admin, &updates, &900_000);
+
+    assert_eq!(
+        client.latest_round_data(&asset),
+        Some(RoundData {
+            round_id: 2,
+            price: normalize_price(105),
+            timestamp: 900_000,
+            updated_at: 900,
+            answered_in_round: 2,
+            decimals: Constants::DECIMALS
+        })
+    );
+    assert_eq!(
+        client.latest_round_data(&carried_asset),
+        Some(RoundData {
+            round_id: 2,
+            price: normalize_price(100),
+            timestamp: 900_000,
+            updated_at: 900,
+            answered_in_round: 1,
+            decimals: Constants::DECIMALS
+        })
+    );
+    assert_eq!(
+        client.round_data(&asset, &1),
+        Some(RoundData {
+            round_id: 1,
+            price: normalize_price(100),
+            timestamp: 600_000,
+            updated_at: 600,
+            answered_in_round: 1,
+            decimals: Constants::DECIMALS
+        })
+    );
+
+    //the round is not written yet
+    assert_eq!(client.round_data(&asset, &3), None);
+}
+
+#[test]
+fn checked_reads_test() {
+    let (env, client, init_data) = init_contract_with_admin();
+
+    let contract_id = BytesN::from_array(&env, &[0; 32]);
+    let asset = init_data.assets.get_unchecked(1).unwrap();
+    let unknown_asset = Address::random(&env);
+
+    env.as_contract(&contract_id, || {
+        assert!(matches!(PriceOracle::try_lastprice(&env, asset.clone()), Err(Error::NotInitialized)));
+    });
+
+    client.set_price(&init_data.admin, &get_updates(&env, init_data.assets.clone(), normalize_price(100)), &600_000);
+
+    assert_eq!(
+        client.checked_lastprice(&asset),
+        PriceData {
+            price: normalize_price(100),
+            timestamp: 600_000,
+            decimals: Constants::DECIMALS
+        }
+    );
+
+    env.as_contract(&contract_id, || {
+        assert!(matches!(PriceOracle::try_lastprice(&env, unknown_asset.clone()), Err(Error::AssetNotFound)));
+        assert!(matches!(PriceOracle::try_price(&env, asset.clone(), 1_200_000), Err(Error::NoDataForTimestamp)));
+        assert!(matches!(
+            PriceOracle::try_x_last_price(&env, asset.clone(), unknown_asset.clone()),
+            Err(Error::AssetNotFound)
+        ));
+    });
+}
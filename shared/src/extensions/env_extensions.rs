@@ -1,4 +1,4 @@
-use soroban_sdk::{panic_with_error, Address, BytesN, Env, Vec};
+use soroban_sdk::{panic_with_error, Address, BytesN, Env, Symbol, Vec};
 
 use crate::constants;
 use crate::extensions;
@@ -7,7 +7,9 @@ use crate::types;
 use constants::Constants;
 use extensions::i128_extensions::I128Extensions;
 use types::{
-    asset_price_key::AssetPriceKey, data_key::DataKey, error::Error, price_data::PriceData,
+    asset_metadata::AssetMetadata, asset_price_key::AssetPriceKey, asset_schedule::AssetSchedule,
+    data_key::DataKey, error::Error, price_data::PriceData, quote_price_key::QuotePriceKey,
+    round_info::RoundInfo, rounding::Rounding,
 };
 
 pub trait EnvExtensions {
@@ -21,6 +23,8 @@ pub trait EnvExtensions {
 
     fn get_price(&self, asset: Address, timestamp: u64) -> Option<i128>;
 
+    fn get_price_data(&self, asset: Address, timestamp: u64) -> Option<PriceData>;
+
     fn set_price(&self, asset: Address, price: i128, timestamp: u64);
 
     fn get_last_timestamp(&self) -> Option<u64>;
@@ -58,6 +62,66 @@ pub trait EnvExtensions {
     fn try_delete_old_price(&self, asset: Address, timestamp: u64, period: u64) -> bool;
 
     fn panic_if_not_admin(&self, invoker: &Address);
+
+    fn get_providers(&self) -> Vec<Address>;
+
+    fn set_providers(&self, providers: Vec<Address>);
+
+    fn is_provider(&self, invoker: &Address) -> bool;
+
+    fn panic_if_not_price_provider(&self, invoker: &Address);
+
+    fn get_asset_metadata(&self, asset: Address) -> Option<AssetMetadata>;
+
+    fn set_asset_metadata(&self, asset: Address, metadata: &AssetMetadata);
+
+    fn get_asset_by_symbol(&self, symbol: Symbol) -> Option<Address>;
+
+    fn get_asset_schedule(&self, asset: Address) -> Option<AssetSchedule>;
+
+    fn set_asset_schedule(&self, asset: Address, schedule: &AssetSchedule);
+
+    fn get_asset_last_timestamp(&self, asset: Address) -> Option<u64>;
+
+    fn set_asset_last_timestamp(&self, asset: Address, timestamp: u64);
+
+    fn get_asset_decimals(&self, asset: Address) -> u32;
+
+    fn set_asset_decimals(&self, asset: Address, decimals: u32);
+
+    fn get_quotes(&self) -> Vec<Address>;
+
+    fn set_quotes(&self, quotes: Vec<Address>);
+
+    fn get_quote_price(&self, asset: Address, quote: Address, timestamp: u64) -> Option<i128>;
+
+    fn set_quote_price(&self, asset: Address, quote: Address, price: i128, timestamp: u64);
+
+    fn try_delete_old_quote_price(&self, asset: Address, quote: Address, timestamp: u64, period: u64) -> bool;
+
+    fn get_price_timestamp(&self, asset: Address, timestamp: u64) -> Option<u64>;
+
+    fn get_last_update(&self, asset: Address, timestamp: u64) -> Option<u64>;
+
+    fn set_last_update(&self, asset: Address, timestamp: u64, last_update: u64);
+
+    fn get_last_round_id(&self) -> Option<u64>;
+
+    fn set_last_round_id(&self, round_id: u64);
+
+    fn get_round(&self, round_id: u64) -> Option<RoundInfo>;
+
+    fn set_round(&self, round_id: u64, round: &RoundInfo);
+
+    fn get_round_id(&self, timestamp: u64) -> Option<u64>;
+
+    fn set_round_id(&self, timestamp: u64, round_id: u64);
+
+    fn try_delete_old_round(&self, timestamp: u64, period: u64) -> bool;
+
+    fn get_answered_in_round(&self, asset: Address, timestamp: u64) -> Option<u64>;
+
+    fn set_answered_in_round(&self, asset: Address, timestamp: u64, round_id: u64);
 }
 
 impl EnvExtensions for Env {
@@ -88,16 +152,24 @@ impl EnvExtensions for Env {
     }
 
     fn get_price(&self, asset: Address, timestamp: u64) -> Option<i128> {
-        //build the key for the price
-        let data_key = DataKey::Price(AssetPriceKey { asset, timestamp });
-
-        //check if the price is available
-        if !self.storage().has(&data_key) {
+        let price = resolve_price(&self, asset, timestamp);
+        if price.is_none() {
             return None;
         }
+        Some(price.unwrap().0)
+    }
 
-        //get the price
-        Some(self.storage().get_unchecked(&data_key).unwrap())
+    fn get_price_data(&self, asset: Address, timestamp: u64) -> Option<PriceData> {
+        let price = resolve_price(&self, asset.clone(), timestamp);
+        if price.is_none() {
+            return None;
+        }
+        let (price, timestamp) = price.unwrap();
+        Some(PriceData {
+            price,
+            timestamp,
+            decimals: self.get_asset_decimals(asset),
+        })
     }
 
     fn set_price(&self, asset: Address, price: i128, timestamp: u64) {
@@ -159,8 +231,9 @@ impl EnvExtensions for Env {
     fn get_prices(&self, asset: Address, records: u32) -> Option<Vec<PriceData>> {
         prices(
             &self,
-            |timestamp| self.get_price(asset.clone(), timestamp),
+            |timestamp| resolve_price(&self, asset.clone(), timestamp),
             records,
+            self.get_asset_decimals(asset.clone()),
         )
     }
 
@@ -181,21 +254,23 @@ impl EnvExtensions for Env {
     ) -> Option<Vec<PriceData>> {
         prices(
             self,
-            |timestamp| get_x_price(&self, &base_asset, &quote_asset, timestamp),
+            |timestamp| get_x_price(&self, &base_asset, &quote_asset, timestamp).map(|price| (price, timestamp)),
             records,
+            Constants::DECIMALS,
         )
     }
 
     fn invoker(&self) -> Option<BytesN<32>> {
-        let last_invoker = self.call_stack().first();
-        if last_invoker.is_none() {
+        //the last frame is the current contract, so the direct caller is the previous one
+        let call_stack = self.call_stack();
+        if call_stack.len() < 2 {
             return None;
         }
-        let unwraped = last_invoker.unwrap();
-        if !unwraped.is_ok() {
+        let last_invoker = call_stack.get_unchecked(call_stack.len() - 2);
+        if !last_invoker.is_ok() {
             return None;
         }
-        Some(unwraped.ok().unwrap().0)
+        Some(last_invoker.ok().unwrap().0)
     }
 
     fn try_delete_data(&self, key: DataKey) -> bool {
@@ -210,14 +285,18 @@ impl EnvExtensions for Env {
         if timestamp < period {
             return false;
         }
-        let data_key = DataKey::Price(AssetPriceKey {
+        let price_key = AssetPriceKey {
             asset,
             timestamp: timestamp - period,
-        });
+        };
+        //the skipped rounds of the scheduled assets only point to the last update
+        self.try_delete_data(DataKey::LastUpdate(price_key.clone()));
+        let data_key = DataKey::Price(price_key.clone());
         if !self.storage().has(&data_key) {
             return false;
         }
         self.storage().remove(&data_key);
+        self.try_delete_data(DataKey::AnsweredInRound(price_key));
         true
     }
 
@@ -226,12 +305,271 @@ impl EnvExtensions for Env {
             panic_with_error!(self, Error::Unauthorized);
         }
     }
+
+    fn get_providers(&self) -> Vec<Address> {
+        if !self.storage().has(&DataKey::Providers) {
+            return Vec::new(&self);
+        }
+        self.storage().get_unchecked(&DataKey::Providers).unwrap()
+    }
+
+    fn set_providers(&self, providers: Vec<Address>) {
+        self.storage().set(&DataKey::Providers, &providers);
+    }
+
+    fn is_provider(&self, invoker: &Address) -> bool {
+        self.get_providers().first_index_of(invoker).is_some()
+    }
+
+    fn panic_if_not_price_provider(&self, invoker: &Address) {
+        //the admin can always set prices, the registered providers are allowed in addition
+        if !self.is_authorized(invoker) && !self.is_provider(invoker) {
+            panic_with_error!(self, Error::Unauthorized);
+        }
+    }
+
+    fn get_asset_metadata(&self, asset: Address) -> Option<AssetMetadata> {
+        let metadata_key = DataKey::AssetMetadata(asset);
+        if !self.storage().has(&metadata_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&metadata_key).unwrap())
+    }
+
+    fn set_asset_metadata(&self, asset: Address, metadata: &AssetMetadata) {
+        //release the previous symbol of the asset
+        let prev_metadata = self.get_asset_metadata(asset.clone());
+        if prev_metadata.is_some() {
+            self.storage().remove(&DataKey::AssetBySymbol(prev_metadata.unwrap().symbol));
+        }
+        self.storage().set(&DataKey::AssetMetadata(asset.clone()), metadata);
+        self.storage().set(&DataKey::AssetBySymbol(metadata.symbol.clone()), &asset);
+    }
+
+    fn get_asset_by_symbol(&self, symbol: Symbol) -> Option<Address> {
+        let symbol_key = DataKey::AssetBySymbol(symbol);
+        if !self.storage().has(&symbol_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&symbol_key).unwrap())
+    }
+
+    fn get_asset_schedule(&self, asset: Address) -> Option<AssetSchedule> {
+        let schedule_key = DataKey::AssetSchedule(asset);
+        if !self.storage().has(&schedule_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&schedule_key).unwrap())
+    }
+
+    fn set_asset_schedule(&self, asset: Address, schedule: &AssetSchedule) {
+        let schedule_key = DataKey::AssetSchedule(asset);
+        if schedule.heartbeat == 0 {
+            if self.storage().has(&schedule_key) {
+                self.storage().remove(&schedule_key);
+            }
+            return;
+        }
+        self.storage().set(&schedule_key, schedule);
+    }
+
+    fn get_asset_last_timestamp(&self, asset: Address) -> Option<u64> {
+        let timestamp_key = DataKey::AssetLastTimestamp(asset);
+        if !self.storage().has(&timestamp_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&timestamp_key).unwrap())
+    }
+
+    fn set_asset_last_timestamp(&self, asset: Address, timestamp: u64) {
+        self.storage().set(&DataKey::AssetLastTimestamp(asset), &timestamp);
+    }
+
+    fn get_asset_decimals(&self, asset: Address) -> u32 {
+        let decimals_key = DataKey::AssetDecimals(asset);
+        if !self.storage().has(&decimals_key) {
+            return Constants::DECIMALS;
+        }
+        self.storage().get_unchecked(&decimals_key).unwrap()
+    }
+
+    fn set_asset_decimals(&self, asset: Address, decimals: u32) {
+        self.storage().set(&DataKey::AssetDecimals(asset), &decimals);
+    }
+
+    fn get_quotes(&self) -> Vec<Address> {
+        if !self.storage().has(&DataKey::Quotes) {
+            return Vec::new(&self);
+        }
+        self.storage().get_unchecked(&DataKey::Quotes).unwrap()
+    }
+
+    fn set_quotes(&self, quotes: Vec<Address>) {
+        self.storage().set(&DataKey::Quotes, &quotes);
+    }
+
+    fn get_quote_price(&self, asset: Address, quote: Address, timestamp: u64) -> Option<i128> {
+        let data_key = DataKey::QuotePrice(QuotePriceKey { asset, quote, timestamp });
+        if !self.storage().has(&data_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&data_key).unwrap())
+    }
+
+    fn set_quote_price(&self, asset: Address, quote: Address, price: i128, timestamp: u64) {
+        let data_key = DataKey::QuotePrice(QuotePriceKey { asset, quote, timestamp });
+        self.storage().set(&data_key, &price);
+    }
+
+    fn try_delete_old_quote_price(&self, asset: Address, quote: Address, timestamp: u64, period: u64) -> bool {
+        if timestamp < period {
+            return false;
+        }
+        self.try_delete_data(DataKey::QuotePrice(QuotePriceKey {
+            asset,
+            quote,
+            timestamp: timestamp - period,
+        }))
+    }
+
+    fn get_price_timestamp(&self, asset: Address, timestamp: u64) -> Option<u64> {
+        let price = resolve_price(&self, asset, timestamp);
+        if price.is_none() {
+            return None;
+        }
+        Some(price.unwrap().1)
+    }
+
+    fn get_last_update(&self, asset: Address, timestamp: u64) -> Option<u64> {
+        let update_key = DataKey::LastUpdate(AssetPriceKey { asset, timestamp });
+        if !self.storage().has(&update_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&update_key).unwrap())
+    }
+
+    fn set_last_update(&self, asset: Address, timestamp: u64, last_update: u64) {
+        self.storage().set(&DataKey::LastUpdate(AssetPriceKey { asset, timestamp }), &last_update);
+    }
+
+    fn get_last_round_id(&self) -> Option<u64> {
+        if !self.storage().has(&DataKey::LastRoundId) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&DataKey::LastRoundId).unwrap())
+    }
+
+    fn set_last_round_id(&self, round_id: u64) {
+        self.storage().set(&DataKey::LastRoundId, &round_id);
+    }
+
+    fn get_round(&self, round_id: u64) -> Option<RoundInfo> {
+        let round_key = DataKey::Round(round_id);
+        if !self.storage().has(&round_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&round_key).unwrap())
+    }
+
+    fn set_round(&self, round_id: u64, round: &RoundInfo) {
+        self.storage().set(&DataKey::Round(round_id), round);
+    }
+
+    fn get_round_id(&self, timestamp: u64) -> Option<u64> {
+        let round_id_key = DataKey::RoundId(timestamp);
+        if !self.storage().has(&round_id_key) {
+            return None;
+        }
+        Some(self.storage().get_unchecked(&round_id_key).unwrap())
+    }
+
+    fn set_round_id(&self, timestamp: u64, round_id: u64) {
+        self.storage().set(&DataKey::RoundId(timestamp), &round_id);
+    }
+
+    fn try_delete_old_round(&self, timestamp: u64, period: u64) -> bool {
+        if timestamp < period {
+            return false;
+        }
+        let round_id = self.get_round_id(timestamp - period);
+        if round_id.is_none() {
+            return false;
+        }
+        self.storage().remove(&DataKey::RoundId(timestamp - period));
+        self.try_delete_data(DataKey::Round(round_id.unwrap()))
+    }
+
+    fn get_answered_in_round(&self, asset: Address, timestamp: u64) -> Option<u64> {
+        let price_timestamp = self.get_price_timestamp(asset.clone(), timestamp);
+        if price_timestamp.is_none() {
+            return None;
+        }
+        let price_timestamp = price_timestamp.unwrap();
+        //only the carried over prices are marked, the rest are answered in the round they were stored in
+        let answered_key = DataKey::AnsweredInRound(AssetPriceKey {
+            asset,
+            timestamp: price_timestamp,
+        });
+        if self.storage().has(&answered_key) {
+            return Some(self.storage().get_unchecked(&answered_key).unwrap());
+        }
+        self.get_round_id(price_timestamp)
+    }
+
+    fn set_answered_in_round(&self, asset: Address, timestamp: u64, round_id: u64) {
+        self.storage().set(&DataKey::AnsweredInRound(AssetPriceKey { asset, timestamp }), &round_id);
+    }
+}
+
+//returns the price in effect at the timestamp along with the timestamp it was stored at
+fn resolve_price(e: &Env, asset: Address, timestamp: u64) -> Option<(i128, u64)> {
+    let price = get_stored_price(e, asset.clone(), timestamp);
+    if price.is_some() {
+        return Some((price.unwrap(), timestamp));
+    }
+
+    //the scheduled assets are not updated every round, so the last update within the heartbeat is in effect
+    let schedule = e.get_asset_schedule(asset.clone());
+    if schedule.is_none() {
+        return None;
+    }
+    let heartbeat = schedule.unwrap().heartbeat;
+
+    //the skipped rounds point to the last update, the rounds after the last update don't need it
+    let mut last_update = e.get_asset_last_timestamp(asset.clone());
+    if last_update.is_none() || last_update.unwrap() > timestamp {
+        last_update = e.get_last_update(asset.clone(), timestamp);
+    }
+    if last_update.is_none() || timestamp - last_update.unwrap() > heartbeat {
+        return None;
+    }
+    let last_update = last_update.unwrap();
+    let price = get_stored_price(e, asset, last_update);
+    if price.is_none() {
+        return None;
+    }
+    Some((price.unwrap(), last_update))
+}
+
+fn get_stored_price(e: &Env, asset: Address, timestamp: u64) -> Option<i128> {
+    //build the key for the price
+    let data_key = DataKey::Price(AssetPriceKey { asset, timestamp });
+
+    //check if the price is available
+    if !e.storage().has(&data_key) {
+        return None;
+    }
+
+    //get the price
+    Some(e.storage().get_unchecked(&data_key).unwrap())
 }
 
-fn prices<F: Fn(u64) -> Option<i128>>(
+//the price function returns the price along with the timestamp it was stored at
+fn prices<F: Fn(u64) -> Option<(i128, u64)>>(
     e: &Env,
     get_price_fn: F,
     records: u32,
+    decimals: u32,
 ) -> Option<Vec<PriceData>> {
     //check if the asset is valid
     let mut timestamp = e.get_last_timestamp().unwrap_or(0);
@@ -253,11 +591,17 @@ fn prices<F: Fn(u64) -> Option<i128>>(
             //TODO: should we put None here?
             continue;
         }
+        let (price, price_timestamp) = price.unwrap();
         prices.push_back(PriceData {
-            price: price.unwrap(),
-            timestamp,
+            price,
+            timestamp: price_timestamp,
+            decimals,
         });
-        timestamp -= resolution;
+        //the scheduled asset price is in effect until the next update, so the records continue before it
+        if price_timestamp < resolution {
+            break;
+        }
+        timestamp = price_timestamp - resolution;
     }
 
     if prices.len() == 0 {
@@ -278,6 +622,12 @@ fn get_x_price(
         return Some(10i128.pow(Constants::DECIMALS));
     }
 
+    //the direct feed of the quote is preferred over the cross price
+    let direct_price = e.get_quote_price(base_asset.clone(), quote_asset.clone(), timestamp);
+    if direct_price.is_some() {
+        return direct_price;
+    }
+
     //get the price for base_asset
     let base_asset_price = e.get_price(base_asset.clone(), timestamp);
     if base_asset_price.is_none() {
@@ -286,14 +636,29 @@ fn get_x_price(
 
     //get the price for quote_asset
     let quote_asset_price = e.get_price(quote_asset.clone(), timestamp);
-    if quote_asset_price.is_none() {
+    if quote_asset_price.is_none() || quote_asset_price.unwrap() == 0 {
         return None;
     }
 
+    //base * 10^(decimals + quote_decimals - base_decimals) / quote in a single step to keep the precision
+    let scale = (Constants::DECIMALS + e.get_asset_decimals(quote_asset.clone())) as i32
+        - e.get_asset_decimals(base_asset.clone()) as i32;
+    let (multiplier, divisor) = if scale >= 0 {
+        (10i128.checked_pow(scale as u32), Some(quote_asset_price.unwrap()))
+    } else {
+        (
+            Some(1),
+            10i128.checked_pow(-scale as u32).and_then(|divisor| divisor.checked_mul(quote_asset_price.unwrap())),
+        )
+    };
+    if multiplier.is_none() || divisor.is_none() {
+        panic_with_error!(e, Error::ConversionOverflow);
+    }
+
     //calculate the cross price
-    Some(
-        base_asset_price
-            .unwrap()
-            .fixed_div_floor(quote_asset_price.unwrap(), Constants::DECIMALS),
-    )
+    let price = base_asset_price.unwrap().mul_div(multiplier.unwrap(), divisor.unwrap(), Rounding::Floor);
+    if price.is_none() {
+        panic_with_error!(e, Error::ConversionOverflow);
+    }
+    price
 }
@@ -1,13 +1,44 @@
 use core::cmp;
 
+use crate::types::rounding::Rounding;
+
 pub trait I128Extensions {
     fn fixed_div_floor(self, y: i128, decimals: u32) -> i128;
+
+    fn rescale(self, from_decimals: u32, to_decimals: u32) -> Option<i128>;
+
+    fn mul_div(self, y: i128, z: i128, rounding: Rounding) -> Option<i128>;
+
+    fn is_deviated(self, prev: i128, deviation: u32) -> bool;
 }
 
 impl I128Extensions for i128 {
     fn fixed_div_floor(self, y: i128, decimals: u32) -> i128 {
         div_floor(self, y, decimals)
     }
+
+    fn rescale(self, from_decimals: u32, to_decimals: u32) -> Option<i128> {
+        if from_decimals > to_decimals {
+            let divisor = 10_i128.checked_pow(from_decimals - to_decimals);
+            if divisor.is_none() {
+                return None;
+            }
+            return Some(self / divisor.unwrap());
+        }
+        let multiplier = 10_i128.checked_pow(to_decimals - from_decimals);
+        if multiplier.is_none() {
+            return None;
+        }
+        self.checked_mul(multiplier.unwrap())
+    }
+
+    fn mul_div(self, y: i128, z: i128, rounding: Rounding) -> Option<i128> {
+        mul_div(self, y, z, rounding)
+    }
+
+    fn is_deviated(self, prev: i128, deviation: u32) -> bool {
+        is_deviated(self, prev, deviation)
+    }
 }
 
 fn div_floor(x: i128, y: i128, decimals: u32) -> i128 {
@@ -25,4 +56,82 @@ fn div_floor(x: i128, y: i128, decimals: u32) -> i128 {
         divisor /= 10_i128.pow(bshift);
     }
     dividend/divisor
-}
\ No newline at end of file
+}
+
+//checks if x deviates from prev by at least the deviation in basis points, 0 disables the check
+fn is_deviated(x: i128, prev: i128, deviation: u32) -> bool {
+    if deviation == 0 {
+        return false;
+    }
+    //the difference that can't be computed is larger than any threshold
+    let diff = x.checked_sub(prev).and_then(|diff| diff.checked_abs()).and_then(|diff| diff.checked_mul(10000));
+    if diff.is_none() {
+        return true;
+    }
+    //the threshold that can't be computed is larger than any difference
+    let threshold = prev.checked_abs().and_then(|prev| prev.checked_mul(deviation as i128));
+    if threshold.is_none() {
+        return false;
+    }
+    diff.unwrap() >= threshold.unwrap()
+}
+
+//computes x * y / z with a 256-bit intermediate product, returns None on division by zero or overflow
+fn mul_div(x: i128, y: i128, z: i128, rounding: Rounding) -> Option<i128> {
+    if z == 0 {
+        return None;
+    }
+    let negative = (x < 0) ^ (y < 0) ^ (z < 0);
+    let (hi, lo) = wide_mul(x.unsigned_abs(), y.unsigned_abs());
+    let divisor = z.unsigned_abs();
+    //the quotient does not fit into 128 bits
+    if hi >= divisor {
+        return None;
+    }
+    let (mut quotient, remainder) = wide_div(hi, lo, divisor);
+    //floor rounds the magnitude of negative results up, ceil rounds the magnitude of positive results up
+    let round_up = remainder != 0 && (negative == (rounding == Rounding::Floor));
+    if round_up {
+        quotient = quotient.checked_add(1)?;
+    }
+    if negative {
+        if quotient > i128::MAX as u128 + 1 {
+            return None;
+        }
+        return Some((quotient as i128).wrapping_neg());
+    }
+    if quotient > i128::MAX as u128 {
+        return None;
+    }
+    Some(quotient as i128)
+}
+
+fn wide_mul(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a0, a1) = (a & mask, a >> 64);
+    let (b0, b1) = (b & mask, b >> 64);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & mask) + (p10 & mask);
+    let lo = (p00 & mask) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+//long division of the 256-bit number by the divisor, the high part must be less than the divisor
+fn wide_div(hi: u128, lo: u128, divisor: u128) -> (u128, u128) {
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+    (quotient, remainder)
+}
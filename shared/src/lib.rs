@@ -3,6 +3,7 @@
 pub mod extensions;
 pub mod types;
 pub mod constants;
+pub mod limits;
 pub mod price_oracle;
 
 mod test;
\ No newline at end of file
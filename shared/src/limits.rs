@@ -0,0 +1,8 @@
+//the constants.rs is generated by the build scripts on release builds, so the fixed limits that are not configured per deployment are kept here
+pub struct Limits;
+impl Limits {
+    pub const MAX_CALLBACKS_PER_ASSET: u32 = 20;
+    pub const MAX_CALLBACKS_PER_ROUND: u32 = 10;
+    pub const MAX_HEARTBEAT_ROUNDS: u64 = 288;
+    pub const MAX_DECIMALS: u32 = 24;
+}
\ No newline at end of file
@@ -1,7 +1,8 @@
-use soroban_sdk::{ Env, Address, panic_with_error, Vec, BytesN };
+use soroban_sdk::{ Env, Address, panic_with_error, Vec, BytesN, Symbol };
 use crate::constants::Constants;
-use crate::extensions::{env_extensions::EnvExtensions, u64_extensions::U64Extensions};
-use crate::types::{ config_data::ConfigData, error::Error, price_data::PriceData };
+use crate::limits::Limits;
+use crate::extensions::{env_extensions::EnvExtensions, i128_extensions::I128Extensions, u64_extensions::U64Extensions};
+use crate::types::{ asset_metadata::AssetMetadata, asset_price_key::AssetPriceKey, asset_schedule::AssetSchedule, config_data::ConfigData, data_key::DataKey, error::Error, price_data::PriceData, round_data::RoundData, round_info::RoundInfo, rounding::Rounding };
 
 pub struct PriceOracle;
 
@@ -33,9 +34,146 @@ impl PriceOracle {
         e.set_assets(presented_assets);
     }
 
-    pub fn set_price(e: &Env, user: Address, updates: Vec<i128>, timestamp: u64) {
+    pub fn set_asset_metadata(e: &Env, user: Address, asset: Address, metadata: AssetMetadata) {
+        e.panic_if_not_admin(&user);
+
+        //the base asset has metadata to convert the amounts to it
+        if asset != Self::base(e) && !is_asset_presented(&e.get_assets(), &asset) {
+            panic_with_error!(&e, Error::AssetNotFound);
+        }
+
+        let symbol_asset = e.get_asset_by_symbol(metadata.symbol.clone());
+        if symbol_asset.is_some() && symbol_asset.unwrap() != asset {
+            panic_with_error!(&e, Error::SymbolAlreadyUsed);
+        }
+
+        e.set_asset_metadata(asset, &metadata);
+    }
+
+    pub fn set_asset_schedule(e: &Env, user: Address, asset: Address, schedule: AssetSchedule) {
+        e.panic_if_not_admin(&user);
+
+        if !is_asset_presented(&e.get_assets(), &asset) {
+            panic_with_error!(&e, Error::AssetNotFound);
+        }
+
+        let resolution = Constants::RESOLUTION as u64;
+        if schedule.heartbeat % resolution != 0
+            || schedule.heartbeat / resolution > Limits::MAX_HEARTBEAT_ROUNDS
+            || schedule.heartbeat > e.get_retention_period().unwrap_or(0)
+            || schedule.deviation > 10000
+        {
+            panic_with_error!(&e, Error::InvalidSchedule);
+        }
+
+        e.set_asset_schedule(asset, &schedule);
+    }
+
+    pub fn set_asset_decimals(e: &Env, user: Address, asset: Address, decimals: u32) {
+        e.panic_if_not_admin(&user);
+
+        if !is_asset_presented(&e.get_assets(), &asset) {
+            panic_with_error!(&e, Error::AssetNotFound);
+        }
+
+        //the stored prices are not rescaled, so the decimals can only be set before the first update
+        let last_timestamp = e.get_last_timestamp().unwrap_or(0);
+        if decimals > Limits::MAX_DECIMALS || e.get_price(asset.clone(), last_timestamp).is_some() {
+            panic_with_error!(&e, Error::InvalidDecimals);
+        }
+
+        e.set_asset_decimals(asset, decimals);
+    }
+
+    pub fn add_quotes(e: &Env, user: Address, quotes: Vec<Address>) {
+        e.panic_if_not_admin(&user);
+
+        let mut presented_quotes = e.get_quotes();
+
+        for quote in quotes.iter() {
+            let quote = quote.unwrap();
+            if is_asset_presented(&presented_quotes, &quote) {
+                panic_with_error!(&e, Error::QuoteAlreadyPresented);
+            }
+            presented_quotes.push_back(quote);
+        }
+
+        e.set_quotes(presented_quotes);
+    }
+
+    pub fn set_quote_price(e: &Env, user: Address, quote: Address, updates: Vec<i128>, timestamp: u64) {
         e.panic_if_not_admin(&user);
 
+        if !is_asset_presented(&e.get_quotes(), &quote) {
+            panic_with_error!(&e, Error::QuoteNotFound);
+        }
+
+        let assets = e.get_assets();
+        if updates.len() != assets.len() {
+            panic_with_error!(&e, Error::InvalidUpdatesLength);
+        }
+
+        let retention_period = e.get_retention_period().unwrap();
+
+        //the quote prices are read at the round timestamps
+        let timestamp = timestamp.get_normalized_timestamp(Constants::RESOLUTION.into());
+
+        for (i, price_data) in updates.iter().enumerate() {
+            if !price_data.is_ok() {
+                panic_with_error!(&e, Error::InvalidPriceValue);
+            }
+            //0 means the asset has no direct feed for the quote in this round
+            let price = price_data.ok().unwrap();
+            if price == 0 {
+                continue;
+            }
+            let asset = assets.get_unchecked(i as u32).unwrap();
+            e.set_quote_price(asset.clone(), quote.clone(), price, timestamp);
+            e.try_delete_old_quote_price(asset, quote.clone(), timestamp, retention_period);
+        }
+
+        let last_timestamp = e.get_last_timestamp();
+        if last_timestamp.is_none() || timestamp > last_timestamp.unwrap() {
+            e.set_last_timestamp(timestamp);
+        }
+    }
+
+    pub fn add_providers(e: &Env, user: Address, providers: Vec<Address>) {
+        e.panic_if_not_admin(&user);
+
+        let mut presented_providers = e.get_providers();
+
+        for provider in providers.iter() {
+            let provider = provider.unwrap();
+            if presented_providers.first_index_of(&provider).is_some() {
+                panic_with_error!(&e, Error::ProviderAlreadyRegistered);
+            }
+            presented_providers.push_back(provider);
+        }
+
+        e.set_providers(presented_providers);
+    }
+
+    pub fn remove_providers(e: &Env, user: Address, providers: Vec<Address>) {
+        e.panic_if_not_admin(&user);
+
+        let mut presented_providers = e.get_providers();
+
+        for provider in providers.iter() {
+            let provider = provider.unwrap();
+            let index = presented_providers.first_index_of(&provider);
+            if index.is_none() {
+                panic_with_error!(&e, Error::ProviderNotFound);
+            }
+            presented_providers.remove(index.unwrap());
+        }
+
+        e.set_providers(presented_providers);
+    }
+
+    pub fn set_price(e: &Env, user: Address, updates: Vec<i128>, timestamp: u64) {
+        e.panic_if_not_price_provider(&user);
+
         let assets = e.get_assets();
         let assets_len = assets.len();
 
@@ -52,6 +190,12 @@ impl PriceOracle {
 
         let prev_timestamp = timestamp - (resolution as u64);
 
+        let is_new_round = e.get_round_id(timestamp).is_none();
+        let round_id = start_round(&e, timestamp);
+        if is_new_round {
+            e.try_delete_old_round(timestamp, retention_period);
+        }
+
         //iterate over the updates
         for (i, price_data) in updates.iter().enumerate() {
             if !price_data.is_ok() {
@@ -61,6 +205,16 @@ impl PriceOracle {
             let asset = assets.get_unchecked(i as u32).unwrap();
 
             let mut price = price_data.ok().unwrap();
+
+            let schedule = e.get_asset_schedule(asset.clone());
+            if schedule.is_some() {
+                //0 means the scheduled asset is not updated in this round
+                if price == 0 || !try_set_scheduled_price(&e, asset.clone(), &schedule.unwrap(), price, timestamp, retention_period) {
+                    skip_scheduled_price(&e, asset, timestamp, retention_period);
+                }
+                continue;
+            }
+
             if price == 0 {
                 if last_timestamp.is_none() {
                     panic_with_error!(&e, Error::NoPrevPrice);
@@ -71,6 +225,14 @@ impl PriceOracle {
                     panic_with_error!(&e, Error::NoPrevPrice);
                 }
                 price = prev_price.unwrap();
+                //the carried over price keeps the round it was answered in
+                let answered_in_round = e.get_answered_in_round(asset.clone(), prev_timestamp);
+                e.set_answered_in_round(asset.clone(), timestamp, answered_in_round.unwrap_or(round_id));
+            } else if !is_new_round {
+                e.try_delete_data(DataKey::AnsweredInRound(AssetPriceKey {
+                    asset: asset.clone(),
+                    timestamp,
+                }));
             }
             //store the new price
             e.set_price(asset.clone(), price, timestamp);
@@ -98,6 +260,10 @@ impl PriceOracle {
         Constants::DECIMALS
     }
 
+    pub fn asset_decimals(e: &Env, asset: Address) -> u32 {
+        e.get_asset_decimals(asset)
+    }
+
     pub fn resolution(_e: &Env) -> u32 {
         //return resolution in seconds
         Constants::RESOLUTION / 1000
@@ -107,6 +273,26 @@ impl PriceOracle {
         e.get_retention_period()
     }
 
+    pub fn asset_metadata(e: &Env, asset: Address) -> Option<AssetMetadata> {
+        e.get_asset_metadata(asset)
+    }
+
+    pub fn asset_by_symbol(e: &Env, symbol: Symbol) -> Option<Address> {
+        e.get_asset_by_symbol(symbol)
+    }
+
+    pub fn asset_schedule(e: &Env, asset: Address) -> Option<AssetSchedule> {
+        e.get_asset_schedule(asset)
+    }
+
+    pub fn quotes(e: &Env) -> Vec<Address> {
+        e.get_quotes()
+    }
+
+    pub fn providers(e: &Env) -> Vec<Address> {
+        e.get_providers()
+    }
+
     pub fn assets(e: &Env) -> Option<Vec<Address>> {
         let assets = e.get_assets();
         if assets.len() == 0 {
@@ -118,17 +304,8 @@ impl PriceOracle {
     pub fn price(e: &Env, asset: Address, timestamp: u64) -> Option<PriceData> {
         let normalized_timestamp = timestamp.get_normalized_timestamp(Constants::RESOLUTION.into());
 
-        //get the price
-        let price = e.get_price(asset, normalized_timestamp);
-
-        if price.is_none() {
-            return None;
-        }
-
-        Some(PriceData {
-            price: price.unwrap(),
-            timestamp: normalized_timestamp,
-        })
+        //get the price, the scheduled assets report the timestamp of the update in effect
+        e.get_price_data(asset, normalized_timestamp)
     }
 
     //Get the price for an asset.
@@ -140,15 +317,7 @@ impl PriceOracle {
         }
 
         //get the price
-        let price = e.get_price(asset, timestamp);
-        if price.is_none() {
-            return None;
-        }
-
-        Some(PriceData {
-            price: price.unwrap(),
-            timestamp,
-        })
+        e.get_price_data(asset, timestamp)
     }
 
     pub fn x_price(
@@ -168,6 +337,7 @@ impl PriceOracle {
         Some(PriceData {
             price: price.unwrap(),
             timestamp: normalized_timestamp,
+            decimals: Constants::DECIMALS,
         })
     }
 
@@ -185,6 +355,7 @@ impl PriceOracle {
         Some(PriceData {
             price: price.unwrap(),
             timestamp,
+            decimals: Constants::DECIMALS,
         })
     }
 
@@ -207,15 +378,7 @@ impl PriceOracle {
             return None;
         }
 
-        let prices = prices_result.unwrap();
-
-        let mut sum = 0;
-        for price in prices.iter() {
-            let price_data = price.unwrap();
-            sum += price_data.price;
-        }
-
-        Some(sum / (prices.len() as i128))
+        Some(Self::average_price(&prices_result.unwrap()))
     }
 
     pub fn x_twap(e: &Env, base_asset: Address, quote_asset: Address, records: u32) -> Option<i128> {
@@ -224,16 +387,335 @@ impl PriceOracle {
             return None;
         }
 
-        let prices = prices_result.unwrap();
+        Some(Self::average_price(&prices_result.unwrap()))
+    }
+
+    pub fn convert(
+        e: &Env,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        rounding: Rounding,
+    ) -> Option<i128> {
+        let timestamp = e.get_last_timestamp().unwrap_or(0);
+        if timestamp == 0 {
+            return None;
+        }
+        convert(e, from_asset, to_asset, amount, rounding, timestamp)
+    }
+
+    pub fn convert_at(
+        e: &Env,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        rounding: Rounding,
+        timestamp: u64,
+    ) -> Option<i128> {
+        let normalized_timestamp = timestamp.get_normalized_timestamp(Constants::RESOLUTION.into());
+        convert(e, from_asset, to_asset, amount, rounding, normalized_timestamp)
+    }
+
+    pub fn latest_round_data(e: &Env, asset: Address) -> Option<RoundData> {
+        let timestamp = e.get_last_timestamp();
+        if timestamp.is_none() {
+            return None;
+        }
+        let round_id = e.get_round_id(timestamp.unwrap());
+        if round_id.is_none() {
+            return None;
+        }
+        PriceOracle::round_data(e, asset, round_id.unwrap())
+    }
+
+    pub fn round_data(e: &Env, asset: Address, round_id: u64) -> Option<RoundData> {
+        let round = e.get_round(round_id);
+        if round.is_none() {
+            return None;
+        }
+        let round = round.unwrap();
+
+        let price = e.get_price(asset.clone(), round.timestamp);
+        if price.is_none() {
+            return None;
+        }
+
+        Some(RoundData {
+            round_id,
+            price: price.unwrap(),
+            timestamp: round.timestamp,
+            updated_at: round.updated_at,
+            answered_in_round: e.get_answered_in_round(asset.clone(), round.timestamp).unwrap_or(round_id),
+            decimals: e.get_asset_decimals(asset),
+        })
+    }
+
+    pub fn try_price(e: &Env, asset: Address, timestamp: u64) -> Result<PriceData, Error> {
+        let price = PriceOracle::price(e, asset.clone(), timestamp);
+        if price.is_none() {
+            let normalized_timestamp = timestamp.get_normalized_timestamp(Constants::RESOLUTION.into());
+            return Err(get_price_error(e, &asset, normalized_timestamp));
+        }
+        Ok(price.unwrap())
+    }
 
+    pub fn try_lastprice(e: &Env, asset: Address) -> Result<PriceData, Error> {
+        let price = PriceOracle::lastprice(e, asset.clone());
+        if price.is_none() {
+            return Err(get_price_error(e, &asset, e.get_last_timestamp().unwrap_or(0)));
+        }
+        Ok(price.unwrap())
+    }
+
+    pub fn try_x_price(
+        e: &Env,
+        base_asset: Address,
+        quote_asset: Address,
+        timestamp: u64,
+    ) -> Result<PriceData, Error> {
+        let price = PriceOracle::x_price(e, base_asset.clone(), quote_asset.clone(), timestamp);
+        if price.is_none() {
+            let normalized_timestamp = timestamp.get_normalized_timestamp(Constants::RESOLUTION.into());
+            return Err(get_x_price_error(e, &base_asset, &quote_asset, normalized_timestamp));
+        }
+        Ok(price.unwrap())
+    }
+
+    pub fn try_x_last_price(e: &Env, base_asset: Address, quote_asset: Address) -> Result<PriceData, Error> {
+        let price = PriceOracle::x_last_price(e, base_asset.clone(), quote_asset.clone());
+        if price.is_none() {
+            let timestamp = e.get_last_timestamp().unwrap_or(0);
+            return Err(get_x_price_error(e, &base_asset, &quote_asset, timestamp));
+        }
+        Ok(price.unwrap())
+    }
+
+    pub fn try_prices(e: &Env, asset: Address, records: u32) -> Result<Vec<PriceData>, Error> {
+        let prices = PriceOracle::prices(e, asset.clone(), records);
+        if prices.is_none() {
+            return Err(get_price_error(e, &asset, e.get_last_timestamp().unwrap_or(0)));
+        }
+        Ok(prices.unwrap())
+    }
+
+    pub fn try_x_prices(
+        e: &Env,
+        base_asset: Address,
+        quote_asset: Address,
+        records: u32,
+    ) -> Result<Vec<PriceData>, Error> {
+        let prices = PriceOracle::x_prices(e, base_asset.clone(), quote_asset.clone(), records);
+        if prices.is_none() {
+            let timestamp = e.get_last_timestamp().unwrap_or(0);
+            return Err(get_x_price_error(e, &base_asset, &quote_asset, timestamp));
+        }
+        Ok(prices.unwrap())
+    }
+
+    pub fn try_twap(e: &Env, asset: Address, records: u32) -> Result<i128, Error> {
+        let prices = PriceOracle::try_prices(e, asset, records)?;
+        Ok(Self::average_price(&prices))
+    }
+
+    pub fn try_x_twap(e: &Env, base_asset: Address, quote_asset: Address, records: u32) -> Result<i128, Error> {
+        let prices = PriceOracle::try_x_prices(e, base_asset, quote_asset, records)?;
+        Ok(Self::average_price(&prices))
+    }
+
+    pub fn average_price(prices: &Vec<PriceData>) -> i128 {
         let mut sum = 0;
         for price in prices.iter() {
             let price_data = price.unwrap();
             sum += price_data.price;
         }
 
-        Some(sum / (prices.len() as i128))
+        sum / (prices.len() as i128)
+    }
+}
+
+fn convert(
+    e: &Env,
+    from_asset: Address,
+    to_asset: Address,
+    amount: i128,
+    rounding: Rounding,
+    timestamp: u64,
+) -> Option<i128> {
+    //the token decimals are taken from the asset metadata
+    let from_metadata = e.get_asset_metadata(from_asset.clone());
+    let to_metadata = e.get_asset_metadata(to_asset.clone());
+    if from_metadata.is_none() || to_metadata.is_none() {
+        return None;
+    }
+
+    let prices = get_conversion_prices(e, from_asset, to_asset, timestamp);
+    if prices.is_none() {
+        return None;
+    }
+    let (from_price, from_price_decimals, to_price, to_price_decimals) = prices.unwrap();
+    if to_price == 0 {
+        return None;
+    }
+
+    //amount * from_price * 10^(to_price_decimals + to_decimals) / (to_price * 10^(from_price_decimals + from_decimals)) in a single step,
+    //so the requested rounding applies to the result
+    let scale = (to_price_decimals + to_metadata.unwrap().decimals) as i32
+        - (from_price_decimals + from_metadata.unwrap().decimals) as i32;
+    let (mut x, mut y, mut z) = (Some(amount), Some(from_price), Some(to_price));
+    if scale >= 0 {
+        let multiplier = 10i128.checked_pow(scale as u32);
+        y = multiplier.and_then(|multiplier| from_price.checked_mul(multiplier));
+        //the multiplier is applied to the amount if the price is too large
+        if y.is_none() {
+            x = multiplier.and_then(|multiplier| amount.checked_mul(multiplier));
+            y = Some(from_price);
+        }
+    } else {
+        z = 10i128.checked_pow(-scale as u32).and_then(|divisor| divisor.checked_mul(to_price));
+    }
+    if x.is_none() || z.is_none() {
+        panic_with_error!(&e, Error::ConversionOverflow);
+    }
+    let result = x.unwrap().mul_div(y.unwrap(), z.unwrap(), rounding);
+    if result.is_none() {
+        panic_with_error!(&e, Error::ConversionOverflow);
+    }
+    result
+}
+
+//returns the prices of the from and to assets in the common asset and their decimals
+fn get_conversion_prices(
+    e: &Env,
+    from_asset: Address,
+    to_asset: Address,
+    timestamp: u64,
+) -> Option<(i128, u32, i128, u32)> {
+    if from_asset == to_asset {
+        return Some((1, 0, 1, 0));
+    }
+
+    //the direct feed of the quote is preferred over the cross price
+    let direct_price = e.get_quote_price(from_asset.clone(), to_asset.clone(), timestamp);
+    if direct_price.is_some() {
+        return Some((direct_price.unwrap(), Constants::DECIMALS, 1, 0));
+    }
+
+    let from_price = get_base_price(e, from_asset, timestamp);
+    let to_price = get_base_price(e, to_asset, timestamp);
+    if from_price.is_none() || to_price.is_none() {
+        return None;
+    }
+    let (from_price, from_price_decimals) = from_price.unwrap();
+    let (to_price, to_price_decimals) = to_price.unwrap();
+    Some((from_price, from_price_decimals, to_price, to_price_decimals))
+}
+
+//returns the price of the asset in the base asset and its decimals
+fn get_base_price(e: &Env, asset: Address, timestamp: u64) -> Option<(i128, u32)> {
+    if asset == PriceOracle::base(e) {
+        return Some((1, 0));
+    }
+    let price = e.get_price(asset.clone(), timestamp);
+    if price.is_none() {
+        return None;
+    }
+    Some((price.unwrap(), e.get_asset_decimals(asset)))
+}
+
+//returns the id of the round for the timestamp, the new rounds get the next id
+fn start_round(e: &Env, timestamp: u64) -> u64 {
+    let mut round_id = e.get_round_id(timestamp);
+    if round_id.is_none() {
+        let last_round_id = e.get_last_round_id();
+        //the ids follow the round timestamps, so the slots older than the latest round can't be backfilled
+        if last_round_id.is_some() {
+            let last_round = e.get_round(last_round_id.unwrap());
+            if last_round.is_some() && last_round.unwrap().timestamp > timestamp {
+                panic_with_error!(&e, Error::InvalidTimestamp);
+            }
+        }
+        let next_round_id = last_round_id.unwrap_or(0) + 1;
+        e.set_last_round_id(next_round_id);
+        e.set_round_id(timestamp, next_round_id);
+        round_id = Some(next_round_id);
+    }
+    let round_id = round_id.unwrap();
+    e.set_round(
+        round_id,
+        &RoundInfo {
+            timestamp,
+            updated_at: e.ledger().timestamp(),
+        },
+    );
+    round_id
+}
+
+fn try_set_scheduled_price(
+    e: &Env,
+    asset: Address,
+    schedule: &AssetSchedule,
+    price: i128,
+    timestamp: u64,
+    retention_period: u64,
+) -> bool {
+    let last_timestamp = e.get_asset_last_timestamp(asset.clone());
+    if last_timestamp.is_some() && timestamp > last_timestamp.unwrap() {
+        let last_timestamp = last_timestamp.unwrap();
+        //the update is due after the heartbeat, or earlier if the price deviates enough
+        let last_price = e.get_price(asset.clone(), last_timestamp).unwrap_or(0);
+        if timestamp - last_timestamp < schedule.heartbeat && !price.is_deviated(last_price, schedule.deviation) {
+            return false;
+        }
+    }
+
+    e.set_price(asset.clone(), price, timestamp);
+    if last_timestamp.is_none() || timestamp > last_timestamp.unwrap() {
+        e.set_asset_last_timestamp(asset.clone(), timestamp);
+    }
+    e.try_delete_old_price(asset, timestamp, retention_period);
+    true
+}
+
+//the skipped round of the scheduled asset points to the last update in effect
+fn skip_scheduled_price(e: &Env, asset: Address, timestamp: u64, retention_period: u64) {
+    let last_timestamp = e.get_asset_last_timestamp(asset.clone());
+    if last_timestamp.is_some() && last_timestamp.unwrap() < timestamp {
+        e.set_last_update(asset.clone(), timestamp, last_timestamp.unwrap());
+    }
+    e.try_delete_old_price(asset, timestamp, retention_period);
+}
+
+//explains why there is no price for the asset at the timestamp
+fn get_price_error(e: &Env, asset: &Address, timestamp: u64) -> Error {
+    let last_timestamp = e.get_last_timestamp();
+    if last_timestamp.is_none() {
+        return Error::NotInitialized;
+    }
+    if !is_asset_presented(&e.get_assets(), asset) {
+        return Error::AssetNotFound;
+    }
+    //the scheduled asset was not updated within the heartbeat
+    if e.get_asset_schedule(asset.clone()).is_some() && timestamp <= last_timestamp.unwrap() {
+        let asset_last_timestamp = e.get_asset_last_timestamp(asset.clone());
+        if asset_last_timestamp.is_some() && asset_last_timestamp.unwrap() < timestamp {
+            return Error::StalePrice;
+        }
+    }
+    Error::NoDataForTimestamp
+}
+
+fn get_x_price_error(e: &Env, base_asset: &Address, quote_asset: &Address, timestamp: u64) -> Error {
+    if e.get_price(base_asset.clone(), timestamp).is_none() {
+        return get_price_error(e, base_asset, timestamp);
+    }
+    let quote_price = e.get_price(quote_asset.clone(), timestamp);
+    if quote_price.is_none() {
+        return get_price_error(e, quote_asset, timestamp);
+    }
+    if quote_price.unwrap() == 0 {
+        return Error::DivisionByZero;
     }
+    Error::NoDataForTimestamp
 }
 
 fn is_asset_presented(assets: &Vec<Address>, asset: &Address) -> bool {
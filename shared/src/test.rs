@@ -8,4 +8,11 @@ fn div_tests() {
     let b = i128::MAX / 42;
     let result = a.fixed_div_floor(b, 14);
     assert_eq!(result, 4200000000000000);
+}
+
+#[test]
+fn rescale_tests() {
+    assert_eq!(1_500i128.rescale(3, 1), Some(15));
+    assert_eq!(15i128.rescale(1, 3), Some(1_500));
+    assert_eq!((i128::MAX / 10).rescale(0, 24), None);
 }
\ No newline at end of file
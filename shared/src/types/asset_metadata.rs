@@ -0,0 +1,15 @@
+use soroban_sdk::{contracttype, Bytes, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The human-readable description of a supported asset.
+pub struct AssetMetadata {
+    /// The ticker symbol. Unique across the supported assets.
+    pub symbol: Symbol,
+    /// The display name.
+    pub name: Bytes,
+    /// The description of the price source.
+    pub description: Bytes,
+    /// The number of decimals of the asset token.
+    pub decimals: u32,
+}
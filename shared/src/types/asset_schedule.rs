@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The update schedule of an asset that is not updated every round.
+pub struct AssetSchedule {
+    /// The maximum interval between the updates in milliseconds. Must be a multiple of the resolution.
+    pub heartbeat: u64,
+    /// The price deviation that triggers an update before the heartbeat in basis points. 0 disables the trigger.
+    pub deviation: u32,
+}
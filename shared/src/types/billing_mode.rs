@@ -0,0 +1,12 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+/// Defines which party is billed for the reads.
+pub enum BillingMode {
+    /// The caller should be the contract invoking the oracle directly, or the transaction source account.
+    Caller = 0,
+    /// The caller can be any authorized address, e.g. the transaction originator of a nested call.
+    Originator = 1,
+}
@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The windows of the two-phase provider submission, relative to the round timestamp.
+pub struct CommitRevealConfig {
+    /// The commit window in milliseconds. The commitments are accepted until the round timestamp plus the window.
+    pub commit_window: u64,
+    /// The reveal window in milliseconds, following the commit window.
+    pub reveal_window: u64,
+}
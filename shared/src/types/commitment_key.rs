@@ -0,0 +1,11 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The key of the provider commitment for a round.
+pub struct CommitmentKey {
+    /// The provider address.
+    pub provider: Address,
+    /// The round timestamp.
+    pub timestamp: u64,
+}
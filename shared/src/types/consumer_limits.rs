@@ -0,0 +1,15 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The limits set by a consumer on its own balance. 0 means no limit.
+pub struct ConsumerLimits {
+    /// The maximum number of paid reads per ledger.
+    pub calls_per_ledger: u32,
+    /// The maximum number of paid reads per period.
+    pub calls_per_period: u32,
+    /// The maximum amount of fees per period.
+    pub fees_per_period: i128,
+    /// The period duration in seconds.
+    pub period: u64,
+}
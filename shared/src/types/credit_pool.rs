@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The additional fee asset tokens backing the credit that is not charged yet.
+pub struct CreditPool {
+    /// The amount of tokens received from the deposits and not moved to the reserve yet.
+    pub tokens: i128,
+    /// The credit issued for the tokens and not charged yet.
+    pub credit: i128,
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{contracttype, BytesN};
+use soroban_sdk::{contracttype, Address, BytesN, Symbol};
 
-use super::asset_price_key::AssetPriceKey;
+use super::{
+    asset_price_key::AssetPriceKey, commitment_key::CommitmentKey, endpoint::Endpoint,
+    quote_price_key::QuotePriceKey, usage_key::UsageKey,
+};
 
 #[contracttype]
 pub enum DataKey {
@@ -10,5 +13,62 @@ pub enum DataKey {
     RetentionPeriod,
     Assets,
     BaseFee,
-    Balance(BytesN<32>)
+    //contract balances stored before the address-based billing, migrated on the first update
+    Balance(BytesN<32>),
+    AccountBalance(Address),
+    SubscriptionPlan(u32),
+    Subscription(Address),
+    Treasury,
+    FeeManager,
+    EndpointFee(Endpoint),
+    AssetFee(Address),
+    SponsorBalance(Address),
+    Sponsorship(Address),
+    FeeAssets,
+    Reserve(Address),
+    CreditBalance(Address),
+    SponsorCredit(Address),
+    CreditPool(Address),
+    CreditAsset(Address),
+    SponsorCreditAsset(Address),
+    FreeTier,
+    FreeUsage(Address),
+    Discount(Address),
+    BillingMode,
+    UsagePeriod,
+    Usage(UsageKey),
+    Limits(Address),
+    LimitsState(Address),
+    LowBalance(Address),
+    TopUp(Address),
+    Providers,
+    RewardPool,
+    ProviderRewards(Address),
+    StakingConfig,
+    ProviderStake(Address),
+    PendingRound,
+    Quorum,
+    Submissions(u64),
+    Signers,
+    SignerNonce(BytesN<32>),
+    PullPrice(Address),
+    CommitRevealConfig,
+    Commitment(CommitmentKey),
+    Committers(u64),
+    CommitRound,
+    MissedReveals(Address),
+    Callbacks(Address),
+    CallbackRound,
+    AssetMetadata(Address),
+    AssetBySymbol(Symbol),
+    AssetSchedule(Address),
+    AssetLastTimestamp(Address),
+    LastUpdate(AssetPriceKey),
+    AssetDecimals(Address),
+    Quotes,
+    QuotePrice(QuotePriceKey),
+    LastRoundId,
+    Round(u64),
+    RoundId(u64),
+    AnsweredInRound(AssetPriceKey)
 }
@@ -0,0 +1,19 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+/// The paid read endpoints of the contract.
+pub enum Endpoint {
+    Price = 1,
+    LastPrice = 2,
+    XPrice = 3,
+    XLastPrice = 4,
+    Prices = 5,
+    XPrices = 6,
+    Twap = 7,
+    XTwap = 8,
+    PullPrice = 9,
+    Callback = 10,
+    Convert = 11,
+}
@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The fee multipliers of an endpoint. The fee is `base_fee * (multiplier + record_multiplier * records)`.
+pub struct EndpointFee {
+    /// The base fee multiplier charged per call.
+    pub multiplier: u32,
+    /// The base fee multiplier charged per returned record.
+    pub record_multiplier: u32,
+}
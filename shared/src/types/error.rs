@@ -20,4 +20,106 @@ pub enum Error {
     InvalidDepositAmount = 12,
     /// Consumer has insufficient balance to pay the fee
     InsufficientBalance = 13,
+    /// Subscription plan with the given id does not exist
+    PlanNotFound = 14,
+    /// Consumer already has an active subscription for another plan
+    SubscriptionAlreadyActive = 15,
+    /// Withdrawal amount has negative or zero value
+    InvalidWithdrawAmount = 16,
+    /// Claim amount has negative or zero value
+    InvalidClaimAmount = 17,
+    /// Claim amount exceeds the earned fees
+    InsufficientFees = 18,
+    /// Consumer is already sponsored by another sponsor
+    AlreadySponsored = 19,
+    /// Consumer is not sponsored by the sponsor
+    SponsorshipNotFound = 20,
+    /// There is no price or token decimals to convert the deposited asset to the fee asset
+    NoConversionPrice = 21,
+    /// The price is older than the allowed age
+    StalePrice = 22,
+    /// The haircut exceeds 100%
+    InvalidHaircut = 23,
+    /// The amount exceeds the reserve of the asset
+    InsufficientReserve = 24,
+    /// The discount exceeds 100%
+    InvalidDiscount = 25,
+    /// The requested range is empty or too long
+    InvalidUsageRange = 26,
+    /// The consumer exceeded its calls limit
+    RateLimitExceeded = 27,
+    /// The consumer exceeded its fees limit
+    SpendingCapExceeded = 28,
+    /// The revenue share exceeds 100%
+    InvalidProviderShare = 29,
+    /// The provider is already registered
+    ProviderAlreadyRegistered = 30,
+    /// The provider is not registered
+    ProviderNotFound = 31,
+    /// The amount exceeds the provider rewards
+    InsufficientRewards = 32,
+    /// The provider stake is below the minimum
+    InsufficientStake = 33,
+    /// The stake amount is invalid
+    InvalidStakeAmount = 34,
+    /// The unbonded stake is still locked
+    StakeLocked = 35,
+    /// The round is already finalized
+    RoundFinalized = 36,
+    /// The deviation or the slash share exceeds 100%
+    InvalidStakingConfig = 37,
+    /// The signer is not registered
+    SignerNotFound = 38,
+    /// The signer is already registered
+    SignerAlreadyRegistered = 39,
+    /// The signed price is issued for another contract, is from the future, or the asset is not supported
+    InvalidSignedPrice = 40,
+    /// The nonce is already used by the signer
+    InvalidNonce = 41,
+    /// The provider prices must be committed and revealed
+    CommitRevealRequired = 42,
+    /// The provider has no commitment for the round
+    CommitmentNotFound = 43,
+    /// The revealed prices do not match the commitment
+    InvalidReveal = 44,
+    /// The commit or reveal window is closed or not open yet
+    OutsideWindow = 45,
+    /// The commit and reveal windows exceed the resolution
+    InvalidCommitRevealConfig = 46,
+    /// The asset has the maximum number of callbacks or the callback has no trigger
+    InvalidCallback = 47,
+    /// The callback is not registered
+    CallbackNotFound = 48,
+    /// The asset is not supported by the contract
+    AssetNotFound = 49,
+    /// The symbol is already used by another asset
+    SymbolAlreadyUsed = 50,
+    /// The heartbeat is not a multiple of the resolution or exceeds the retention period
+    InvalidSchedule = 51,
+    /// The decimals exceed the maximum, or the asset already has prices
+    InvalidDecimals = 52,
+    /// The quote is not added to the contract's list of quotes
+    QuoteNotFound = 53,
+    /// The quote is already added to the contract's list of quotes
+    QuoteAlreadyPresented = 54,
+    /// The converted amount or the rescaled price does not fit into i128
+    ConversionOverflow = 55,
+    /// There is no price for the timestamp
+    NoDataForTimestamp = 56,
+    /// The contract has no prices yet
+    NotInitialized = 57,
+    /// The quote price is zero
+    DivisionByZero = 58,
+    /// The usage period is zero
+    InvalidUsagePeriod = 59,
+    /// The limits period is zero while a per-period limit is set
+    InvalidLimits = 60,
+    /// The plan price is negative or the duration is zero
+    InvalidPlan = 61,
+    /// The credit is backed by another additional fee asset
+    CreditAssetMismatch = 62,
+    /// The timestamp is ahead of the ledger time or older than the latest round
+    InvalidTimestamp = 63,
+    /// The quorum is zero
+    InvalidQuorum = 64,
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use soroban_sdk::{contracttype, Address, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The arguments of an endpoint call used to quote the fee.
+pub struct FeeArgs {
+    /// The assets requested by the call.
+    pub assets: Vec<Address>,
+    /// The number of returned records. 0 for single price endpoints.
+    pub records: u32,
+}
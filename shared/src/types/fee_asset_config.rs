@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The conversion parameters of an additional fee asset.
+pub struct FeeAssetConfig {
+    /// The haircut applied to the converted amount in basis points.
+    pub haircut: u32,
+    /// The maximum age of the conversion price in seconds.
+    pub max_price_age: u64,
+}
@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The free quota granted to every consumer.
+pub struct FreeTier {
+    /// The number of free calls per period.
+    pub calls: u32,
+    /// The period duration in seconds.
+    pub period: u64,
+}
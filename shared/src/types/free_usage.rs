@@ -0,0 +1,11 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The free quota usage of a consumer.
+pub struct FreeUsage {
+    /// The start of the current period in seconds.
+    pub period_start: u64,
+    /// The number of free calls made during the current period.
+    pub calls: u32,
+}
@@ -0,0 +1,17 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The consumption tracked against the consumer limits.
+pub struct LimitsState {
+    /// The last ledger sequence with reads.
+    pub ledger: u32,
+    /// The number of reads in the last ledger.
+    pub ledger_calls: u32,
+    /// The start of the current period in seconds.
+    pub period_start: u64,
+    /// The number of reads in the current period.
+    pub period_calls: u32,
+    /// The amount of fees in the current period.
+    pub period_fees: i128,
+}
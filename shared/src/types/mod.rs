@@ -2,4 +2,35 @@ pub mod data_key;
 pub mod asset_price_key;
 pub mod error;
 pub mod price_data;
-pub mod config_data;
\ No newline at end of file
+pub mod config_data;
+pub mod subscription_plan;
+pub mod subscription;
+pub mod treasury;
+pub mod endpoint;
+pub mod endpoint_fee;
+pub mod fee_args;
+pub mod sponsorship;
+pub mod fee_asset_config;
+pub mod free_tier;
+pub mod free_usage;
+pub mod billing_mode;
+pub mod usage_key;
+pub mod usage_data;
+pub mod consumer_limits;
+pub mod limits_state;
+pub mod top_up;
+pub mod reward_pool;
+pub mod provider_rewards;
+pub mod staking_config;
+pub mod provider_stake;
+pub mod signed_price;
+pub mod commit_reveal_config;
+pub mod commitment_key;
+pub mod price_callback;
+pub mod asset_metadata;
+pub mod asset_schedule;
+pub mod quote_price_key;
+pub mod rounding;
+pub mod round_data;
+pub mod round_info;
+pub mod credit_pool;
\ No newline at end of file
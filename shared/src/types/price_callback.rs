@@ -0,0 +1,19 @@
+use soroban_sdk::{contracttype, BytesN, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The consumer callback invoked on the asset price updates.
+pub struct PriceCallback {
+    /// The consumer contract id.
+    pub contract_id: BytesN<32>,
+    /// The consumer function invoked with the asset address and the price data.
+    pub function: Symbol,
+    /// The price deviation that triggers the callback in basis points. 0 disables the trigger.
+    pub deviation: u32,
+    /// The time after the last invocation that triggers the callback in milliseconds. 0 disables the trigger.
+    pub heartbeat: u64,
+    /// The price passed to the last invocation.
+    pub last_price: i128,
+    /// The timestamp of the price passed to the last invocation.
+    pub last_timestamp: u64,
+}
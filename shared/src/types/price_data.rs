@@ -4,8 +4,10 @@ use soroban_sdk::contracttype;
 #[derive(Clone, Debug, Eq, PartialEq)]
 /// The price data for an asset at a given timestamp.
 pub struct PriceData {
-    /// The price in contracts' base asset and decimals.
+    /// The price in contracts' base asset.
     pub price: i128,
     /// The timestamp of the price.
     pub timestamp: u64,
+    /// The number of decimals of the price.
+    pub decimals: u32,
 }
@@ -0,0 +1,13 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The rewards accounting of a price provider.
+pub struct ProviderRewards {
+    /// The number of rounds contributed by the provider.
+    pub rounds: u64,
+    /// The scaled reward per round already accounted for the contributed rounds.
+    pub reward_debt: i128,
+    /// The settled rewards that are not claimed yet.
+    pub unclaimed: i128,
+}
@@ -0,0 +1,13 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The stake of a price provider.
+pub struct ProviderStake {
+    /// The bonded amount.
+    pub amount: i128,
+    /// The unbonded amount that is still slashable.
+    pub unbonding: i128,
+    /// The time when the unbonded amount can be withdrawn in seconds.
+    pub unbonding_until: u64,
+}
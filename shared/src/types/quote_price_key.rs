@@ -0,0 +1,9 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuotePriceKey {
+    pub asset: Address,
+    pub quote: Address,
+    pub timestamp: u64,
+}
\ No newline at end of file
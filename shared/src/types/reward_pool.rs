@@ -0,0 +1,13 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The share of the earned fees distributed to the price providers.
+pub struct RewardPool {
+    /// The share of each charged fee in basis points.
+    pub share: u32,
+    /// The total number of rounds contributed by the registered providers.
+    pub total_rounds: u64,
+    /// The accumulated reward per contributed round, scaled by the price decimals.
+    pub reward_per_round: i128,
+}
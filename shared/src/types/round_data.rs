@@ -0,0 +1,19 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The price data for an asset in a given round.
+pub struct RoundData {
+    /// The round id. The ids are increasing in the order the rounds were written.
+    pub round_id: u64,
+    /// The price in contracts' base asset.
+    pub price: i128,
+    /// The timestamp of the round slot in milliseconds.
+    pub timestamp: u64,
+    /// The ledger timestamp the round was last written at in seconds.
+    pub updated_at: u64,
+    /// The id of the round the price was submitted in. Lower than the round id if the price was carried over.
+    pub answered_in_round: u64,
+    /// The number of decimals of the price.
+    pub decimals: u32,
+}
\ No newline at end of file
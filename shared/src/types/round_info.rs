@@ -0,0 +1,8 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundInfo {
+    pub timestamp: u64,
+    pub updated_at: u64,
+}
\ No newline at end of file
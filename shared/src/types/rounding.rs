@@ -0,0 +1,12 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+/// The rounding direction of the fixed-point computations.
+pub enum Rounding {
+    /// Round towards negative infinity.
+    Floor = 0,
+    /// Round towards positive infinity.
+    Ceil = 1,
+}
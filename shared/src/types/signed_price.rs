@@ -0,0 +1,17 @@
+use soroban_sdk::{contracttype, Address, BytesN};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The price payload signed by an off-chain signer.
+pub struct SignedPrice {
+    /// The asset address.
+    pub asset: Address,
+    /// The price in contracts' base asset and the asset decimals.
+    pub price: i128,
+    /// The timestamp of the price in milliseconds.
+    pub timestamp: u64,
+    /// The id of the contract the payload is signed for.
+    pub contract_id: BytesN<32>,
+    /// The signer nonce. Must be greater than the last used one.
+    pub nonce: u64,
+}
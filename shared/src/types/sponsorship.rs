@@ -0,0 +1,13 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The sponsorship of a consumer by a sponsor account.
+pub struct Sponsorship {
+    /// The sponsor address.
+    pub sponsor: Address,
+    /// The maximum amount the consumer can spend from the sponsor balance. 0 means no cap.
+    pub cap: i128,
+    /// The amount the consumer spent from the sponsor balance.
+    pub spent: i128,
+}
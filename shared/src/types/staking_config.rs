@@ -0,0 +1,15 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The staking requirements of the price providers.
+pub struct StakingConfig {
+    /// The minimum stake required to submit prices.
+    pub min_stake: i128,
+    /// The delay before the unbonded stake can be withdrawn in seconds.
+    pub unbonding_period: u64,
+    /// The maximum deviation of a submission from the round median in basis points. 0 disables the automatic slashing.
+    pub max_deviation: u32,
+    /// The share of the stake slashed for a deviating submission in basis points.
+    pub slash_share: u32,
+}
@@ -0,0 +1,17 @@
+use soroban_sdk::{contracttype, Address, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The active subscription of a consumer.
+pub struct Subscription {
+    /// The plan id the subscription was purchased for.
+    pub plan_id: u32,
+    /// The ledger timestamp (in seconds) when the subscription expires.
+    pub expiration: u64,
+    /// The maximum number of reads during the subscription. 0 means unlimited.
+    pub max_calls: u32,
+    /// The number of reads made during the subscription.
+    pub calls: u32,
+    /// The assets covered by the subscription. Empty vector means all assets.
+    pub assets: Vec<Address>,
+}
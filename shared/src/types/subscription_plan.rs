@@ -0,0 +1,15 @@
+use soroban_sdk::{contracttype, Address, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The subscription plan offered by the contract.
+pub struct SubscriptionPlan {
+    /// The price of the plan in fee asset.
+    pub price: i128,
+    /// The duration of the subscription in seconds.
+    pub duration: u64,
+    /// The maximum number of reads during the subscription. 0 means unlimited.
+    pub max_calls: u32,
+    /// The assets covered by the plan. Empty vector means all assets.
+    pub assets: Vec<Address>,
+}
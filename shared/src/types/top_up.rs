@@ -0,0 +1,11 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The automatic top-up of a consumer balance.
+pub struct TopUp {
+    /// The address the fee tokens are pulled from. The contract must be allowed to spend them.
+    pub funder: Address,
+    /// The amount of fee tokens pulled on each top-up.
+    pub amount: i128,
+}
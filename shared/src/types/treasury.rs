@@ -0,0 +1,19 @@
+use soroban_sdk::contracttype;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The accounting of the fee asset held by the contract.
+pub struct Treasury {
+    /// The total amount deposited by consumers and not yet charged or withdrawn.
+    pub deposits: i128,
+    /// The total amount of earned fees that are not claimed yet.
+    pub earned: i128,
+    /// The total amount of claimed fees.
+    pub claimed: i128,
+    /// The total amount of fees accrued to the price providers and not claimed yet.
+    pub rewards: i128,
+    /// The total amount staked by the price providers, including the unbonding stakes.
+    pub stakes: i128,
+    /// The total credit from the deposits in additional fee assets that is not charged yet. It is backed by the additional fee asset tokens in the credit pools, not by the fee asset.
+    pub credits: i128,
+}
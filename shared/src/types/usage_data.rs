@@ -0,0 +1,15 @@
+use soroban_sdk::{contracttype, Map};
+
+use super::endpoint::Endpoint;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The usage of a consumer during a period.
+pub struct UsageData {
+    /// The start of the period in seconds.
+    pub period: u64,
+    /// The number of delivered reads by endpoint.
+    pub calls: Map<Endpoint, u32>,
+    /// The total amount of fees paid.
+    pub fees: i128,
+}
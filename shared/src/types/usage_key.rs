@@ -0,0 +1,8 @@
+use soroban_sdk::{contracttype, Address};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsageKey {
+    pub account: Address,
+    pub period: u64,
+}